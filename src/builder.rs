@@ -1,81 +1,178 @@
+#[cfg(test)]
+mod test;
+
+use std::ops::Range;
+
 use failure::format_err;
 
-use crate::font::FontDir;
+use crate::condense::condense;
+use crate::font::FontSet;
 use crate::model::array::Array;
-use crate::model::color::ColorSet;
-use crate::model::command::{Command, Space, Text};
+use crate::model::color::{Color, ColorSet};
+use crate::model::command::{Align, Command, Effect, Pause, Space, Text};
+use crate::model::style::{Style, StyleSet};
 use crate::result::Result;
 
 #[derive(Debug)]
 pub struct ArrayBuilder {
     array: Array,
+    layout: Layout,
     color_set: ColorSet,
-    font_dir: FontDir,
+    style_set: StyleSet,
+    font_set: FontSet,
+    panel_width: Option<usize>,
 }
 
 impl ArrayBuilder {
-    pub fn new(color_set: ColorSet, font_dir: FontDir) -> ArrayBuilder {
-        let height = font_dir.height();
+    pub fn new(color_set: ColorSet, style_set: StyleSet, font_set: FontSet) -> ArrayBuilder {
+        let height = font_set.height();
 
         ArrayBuilder {
             array: Array::new(height),
+            layout: Layout::default(),
             color_set,
-            font_dir,
+            style_set,
+            font_set,
+            panel_width: None,
         }
     }
 
-    pub fn process<'a, C, T, S>(&mut self, command: &'a C) -> Result<()>
+    pub fn set_panel_width(&mut self, width: Option<usize>) {
+        self.panel_width = width;
+    }
+
+    pub fn process<'a, C, T, S, P>(&mut self, command: &'a C) -> Result<()>
     where
-        C: Command<'a, T, S>,
+        C: Command<'a, T, S, P>,
         T: Text,
         S: Space,
+        P: Pause,
     {
         command.when_text(Ok(()), |text| self.process_text(text))?;
         command.when_space(Ok(()), |space| self.process_space(space))?;
+        command.when_pause(Ok(()), |pause| self.process_pause(pause))?;
 
         Ok(())
     }
 
+    fn style(&self, name: Option<&str>) -> Result<Style> {
+        match name {
+            Some(name) => Ok(self.style_set.get(name)?.clone()),
+            None => Ok(Style::default()),
+        }
+    }
+
     fn process_text(&mut self, text: &Text) -> Result<()> {
+        let style = self.style(text.style())?.under(text);
+
         let fore = self
             .color_set
-            .get(text.foreground().unwrap_or("_foreground"))
+            .get(
+                style
+                    .foreground
+                    .as_ref()
+                    .map(|s| s.as_str())
+                    .unwrap_or("_foreground"),
+            )
             .map_err(|e| format_err!("unknown foreground color '{}'", e))?;
         let back = self
             .color_set
-            .get(text.background().unwrap_or("_background"))
+            .get(
+                style
+                    .background
+                    .as_ref()
+                    .map(|s| s.as_str())
+                    .unwrap_or("_background"),
+            )
             .map_err(|e| format_err!("unknown background color '{}'", e))?;
 
-        for c in text.content().chars() {
-            let ch = self.font_dir.get_char(c)?;
+        let font_dir = self.font_set.get(style.font.as_ref().map(|s| s.as_str()))?;
+        let spacing = style.spacing.unwrap_or(0);
+
+        let height = self.array.height();
+        let mut columns = Vec::new();
+        let mut glyphs = Vec::new();
+
+        for (i, c) in text.content().chars().enumerate() {
+            let ch = font_dir.get_char(c)?;
 
             let (w, h) = ch.dim();
-            if self.array.height() != h {
+            if height != h {
                 return Err(format_err!("height mismatch"));
             }
 
-            for x in 0..w {
-                let mut line = Vec::new();
+            // Spacing goes between glyphs only, so it is not measured after the last one.
+            if i > 0 {
+                columns.extend((0..spacing).map(|_| 0));
+            }
+            glyphs.push(columns.len()..columns.len() + w);
+            columns.extend((0..w).map(|x| ch.column(x)));
+        }
+
+        if let Some(fit) = style.fit.resolve() {
+            let width = fit
+                .width
+                .or(self.panel_width)
+                .ok_or_else(|| format_err!("fit requires a width or a panel width"))?;
+            let condensed = condense(&columns, width, fit.min_ratio)
+                .map_err(|e| format_err!("text '{}': {}", text.content(), e))?;
+
+            // Glyphs are merged while condensing, so their spans are only approximated.
+            let scale = |x: usize| x * condensed.len() / columns.len().max(1);
+            glyphs = glyphs
+                .into_iter()
+                .map(|g| scale(g.start)..scale(g.end))
+                .collect();
+            columns = condensed;
+        }
 
-                for y in 0..h {
-                    if ch.get(x, y) {
-                        line.push(fore.clone());
+        let start = self.array.width();
+        self.layout
+            .glyphs
+            .extend(glyphs.into_iter().map(|g| start + g.start..start + g.end));
+
+        let effect = style
+            .effect
+            .resolve()
+            .map_err(|e| format_err!("text '{}': {}", text.content(), e))?;
+        if let Some(effect) = effect {
+            self.layout.effects.push(EffectSpan {
+                start,
+                end: start + columns.len(),
+                effect,
+                foreground: fore.clone(),
+                background: back.clone(),
+            });
+        }
+
+        for column in columns {
+            let line = (0..height)
+                .map(|y| {
+                    if column & (1 << y) != 0 {
+                        fore.clone()
                     } else {
-                        line.push(back.clone());
+                        back.clone()
                     }
-                }
+                })
+                .collect();
 
-                self.array.add_line(line);
-            }
+            self.array.add_line(line);
         }
 
         Ok(())
     }
 
     fn process_space(&mut self, space: &Space) -> Result<()> {
+        let style = self.style(space.style())?;
+
         let back = self
             .color_set
-            .get(space.background().unwrap_or("_background"))
+            .get(
+                space
+                    .background()
+                    .or_else(|| style.background.as_ref().map(|s| s.as_str()))
+                    .unwrap_or("_background"),
+            )
             .map_err(|e| format_err!("unknown background color '{}'", e))?;
 
         let mut line = Vec::new();
@@ -88,7 +185,67 @@ impl ArrayBuilder {
         Ok(())
     }
 
-    pub fn finish(self) -> Array {
-        self.array
+    fn process_pause(&mut self, pause: &dyn Pause) -> Result<()> {
+        let hold = match (pause.frames(), pause.seconds()) {
+            (Some(frames), None) => Hold::Frames(frames),
+            (None, Some(seconds)) => Hold::Seconds(seconds),
+            _ => return Err(format_err!("pause needs either frames or seconds")),
+        };
+
+        self.layout.pauses.push(PauseMark {
+            column: self.array.width(),
+            hold,
+            align: pause.align(),
+        });
+
+        Ok(())
+    }
+
+    pub fn finish(self) -> (Array, Layout) {
+        (self.array, self.layout)
+    }
+}
+
+/// Positions recorded while building, in columns of the built array.
+#[derive(Debug, Clone, Default)]
+pub struct Layout {
+    pub pauses: Vec<PauseMark>,
+    /// Columns of each drawn character, in order.
+    pub glyphs: Vec<Range<usize>>,
+    pub effects: Vec<EffectSpan>,
+}
+
+#[derive(Debug, Clone)]
+pub struct PauseMark {
+    pub column: usize,
+    pub hold: Hold,
+    pub align: Align,
+}
+
+/// Columns `start..end` of a text drawn with `foreground` on `background`.
+#[derive(Debug, Clone)]
+pub struct EffectSpan {
+    pub start: usize,
+    pub end: usize,
+    pub effect: Effect,
+    pub foreground: Color,
+    pub background: Color,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Hold {
+    Frames(usize),
+    Seconds(f32),
+}
+
+impl Hold {
+    pub fn frames(self, fps: Option<f32>) -> Result<usize> {
+        match self {
+            Hold::Frames(frames) => Ok(frames),
+            Hold::Seconds(seconds) => {
+                let fps = fps.ok_or_else(|| format_err!("pause in seconds requires meta.fps"))?;
+                Ok((seconds * fps).round() as usize)
+            }
+        }
     }
 }
@@ -0,0 +1,15 @@
+use crate::render::Renderer;
+use crate::source::{Command, Metadata};
+
+#[test]
+fn test_spacing_between_glyphs() {
+    let meta: Metadata = serde_yaml::from_str(
+        "{colors: {_background: '#000000', _foreground: '#ffffff'}, font: test}",
+    )
+    .unwrap();
+    let body: Vec<Command> = serde_yaml::from_str("- text: {c: abc, spacing: 2}").unwrap();
+    let (array, layout) = Renderer::with_test_font(&meta).build(body.iter()).unwrap();
+
+    assert_eq!(array.width(), 7);
+    assert_eq!(layout.glyphs, [0..1, 3..4, 6..7]);
+}
@@ -0,0 +1,124 @@
+#[cfg(test)]
+mod test;
+
+use failure::format_err;
+
+use crate::result::Result;
+
+/// Narrows bit-mask columns (bit `y` set means lit) down to `width` columns.
+///
+/// Blank runs are shortened first, keeping at least one column between inked runs.
+/// If that is not enough, each inked run is resampled horizontally.
+/// `min_ratio` limits how much the inked columns may be compressed.
+pub fn condense(columns: &[u64], width: usize, min_ratio: f32) -> Result<Vec<u64>> {
+    if columns.len() <= width {
+        return Ok(columns.to_vec());
+    }
+
+    let mut runs = split_runs(columns);
+    let mut excess = columns.len() - width;
+
+    excess -= shrink_gaps(&mut runs, excess);
+
+    if excess == 0 {
+        return Ok(join_runs(&runs));
+    }
+
+    let ink_width: usize = runs.iter().filter(|r| r.ink).map(|r| r.columns.len()).sum();
+    let mut ink_runs = runs.iter().filter(|r| r.ink).count();
+
+    let target_ink = ink_width as isize - excess as isize;
+    let ratio = target_ink as f32 / ink_width as f32;
+
+    if target_ink < ink_runs as isize || ratio < min_ratio {
+        return Err(format_err!(
+            "cannot condense {} columns into {}: glyphs would be scaled to {:.2}, below minimum ratio {:.2}",
+            columns.len(),
+            width,
+            ratio.max(0.0),
+            min_ratio
+        ));
+    }
+
+    let mut remaining = target_ink as usize;
+    let mut remaining_src = ink_width;
+    for run in runs.iter_mut().filter(|r| r.ink) {
+        let src = run.columns.len();
+        let dst = ((src * remaining + remaining_src / 2) / remaining_src)
+            .max(1)
+            .min(remaining - (ink_runs - 1));
+        remaining -= dst;
+        remaining_src -= src;
+        ink_runs -= 1;
+        run.columns = resample(&run.columns, dst);
+    }
+
+    Ok(join_runs(&runs))
+}
+
+#[derive(Debug)]
+struct Run {
+    ink: bool,
+    columns: Vec<u64>,
+}
+
+fn split_runs(columns: &[u64]) -> Vec<Run> {
+    let mut runs: Vec<Run> = Vec::new();
+
+    for &c in columns {
+        let ink = c != 0;
+        match runs.last_mut() {
+            Some(run) if run.ink == ink => run.columns.push(c),
+            _ => runs.push(Run {
+                ink,
+                columns: vec![c],
+            }),
+        }
+    }
+
+    runs
+}
+
+fn join_runs(runs: &[Run]) -> Vec<u64> {
+    runs.iter().flat_map(|r| r.columns.iter().cloned()).collect()
+}
+
+/// Removes up to `excess` blank columns, always from the currently longest gap.
+/// Returns the number of removed columns.
+fn shrink_gaps(runs: &mut [Run], excess: usize) -> usize {
+    let last = runs.len() - 1;
+    let min_len = |i: usize| if i == 0 || i == last { 0 } else { 1 };
+
+    let mut removed = 0;
+    while removed < excess {
+        let longest = runs
+            .iter()
+            .enumerate()
+            .filter(|&(i, r)| !r.ink && r.columns.len() > min_len(i))
+            .max_by_key(|&(i, r)| (r.columns.len() - min_len(i), i))
+            .map(|(i, _)| i);
+
+        match longest {
+            Some(i) => {
+                runs[i].columns.pop();
+                removed += 1;
+            }
+            None => break,
+        }
+    }
+
+    removed
+}
+
+/// Resamples columns to `width` columns, merging lit cells that fall into the same column.
+fn resample(columns: &[u64], width: usize) -> Vec<u64> {
+    let src = columns.len();
+
+    (0..width)
+        .map(|i| {
+            let begin = i * src / width;
+            let end = ((i + 1) * src / width).max(begin + 1);
+            columns[begin..end].iter().fold(0, |acc, &c| acc | c)
+        })
+        .collect()
+}
@@ -0,0 +1,43 @@
+use super::*;
+
+#[test]
+fn test_condense_keeps_fitting_columns() {
+    let columns = vec![0b11, 0, 0b01, 0];
+
+    assert_eq!(condense(&columns, 4, 0.5).unwrap(), columns);
+    assert_eq!(condense(&columns, 10, 0.5).unwrap(), columns);
+}
+
+#[test]
+fn test_condense_shrinks_gaps_first() {
+    let columns = vec![0, 0b1, 0, 0, 0, 0b1, 0b1, 0, 0, 0b1, 0];
+
+    // the longest gap is shortened first
+    assert_eq!(
+        condense(&columns, 9, 1.0).unwrap(),
+        vec![0, 0b1, 0, 0, 0b1, 0b1, 0, 0, 0b1]
+    );
+    assert_eq!(
+        condense(&columns, 6, 1.0).unwrap(),
+        vec![0b1, 0, 0b1, 0b1, 0, 0b1]
+    );
+}
+
+#[test]
+fn test_condense_resamples_glyphs() {
+    let columns = vec![0b001, 0b010, 0b100, 0b010, 0, 0b1, 0b1];
+
+    // gaps are already minimal, so glyphs are merged column-wise
+    assert_eq!(
+        condense(&columns, 5, 0.5).unwrap(),
+        vec![0b001, 0b010, 0b110, 0, 0b1]
+    );
+}
+
+#[test]
+fn test_condense_respects_min_ratio() {
+    let columns = vec![0b1; 10];
+
+    assert!(condense(&columns, 6, 0.5).is_ok());
+    assert!(condense(&columns, 4, 0.5).is_err());
+}
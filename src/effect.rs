@@ -0,0 +1,73 @@
+#[cfg(test)]
+mod test;
+
+use std::f32::consts::PI;
+
+use failure::format_err;
+
+use crate::builder::EffectSpan;
+use crate::model::array::Array;
+use crate::model::color::Color;
+use crate::model::command::EffectKind;
+use crate::result::Result;
+
+/// Most frames a cycle of effects may take.
+const MAX_CYCLE_FRAMES: usize = 10_000;
+
+/// Returns `array` as it looks `time` seconds after the start, with the effects of `spans`.
+/// Only the columns of each span are changed.
+pub fn apply(array: &Array, spans: &[EffectSpan], time: f32) -> Array {
+    let mut array = array.clone();
+
+    for span in spans {
+        array.update_columns(span.start..span.end, |color| span_color(span, color, time));
+    }
+
+    array
+}
+
+/// Number of frames until all effects of `spans` repeat together,
+/// the least common multiple of the frames of each period.
+///
+/// Fails if that is more than `MAX_CYCLE_FRAMES`.
+pub fn cycle_frames(spans: &[EffectSpan], fps: f32) -> Result<usize> {
+    spans.iter().try_fold(1, |cycle, span| {
+        let frames = ((span.effect.period * fps).round() as usize).max(1);
+
+        (cycle / gcd(cycle, frames))
+            .checked_mul(frames)
+            .filter(|&lcm| lcm <= MAX_CYCLE_FRAMES)
+            .ok_or_else(|| {
+                format_err!(
+                    "effect periods repeat together only after more than {} frames",
+                    MAX_CYCLE_FRAMES
+                )
+            })
+    })
+}
+
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+fn span_color(span: &EffectSpan, color: &Color, time: f32) -> Color {
+    let effect = span.effect;
+    let phase = (time / effect.period).fract();
+    let on = phase < effect.duty;
+
+    match effect.kind {
+        EffectKind::Blink if !on && *color == span.foreground => span.background.clone(),
+        EffectKind::InvertBlink if !on && *color == span.foreground => span.background.clone(),
+        EffectKind::InvertBlink if !on && *color == span.background => span.foreground.clone(),
+        EffectKind::Flash if *color == span.foreground => {
+            // Full brightness at the start of a cycle, fading to the background halfway.
+            let level = 0.5 + 0.5 * (2.0 * PI * phase).cos();
+            span.background.mix(&span.foreground, level)
+        }
+        _ => color.clone(),
+    }
+}
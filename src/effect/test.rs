@@ -0,0 +1,82 @@
+use super::*;
+use crate::model::command::Effect;
+
+fn span(kind: EffectKind, fore: &Color, back: &Color) -> EffectSpan {
+    EffectSpan {
+        start: 1,
+        end: 2,
+        effect: Effect {
+            kind,
+            period: 1.0,
+            duty: 0.5,
+        },
+        foreground: fore.clone(),
+        background: back.clone(),
+    }
+}
+
+#[test]
+fn test_blink() {
+    let black = Color::new(0u8, 0u8, 0u8, 255u8);
+    let white = Color::new(255u8, 255u8, 255u8, 255u8);
+    let array = Array::filled(3, 1, &white);
+    let spans = [span(EffectKind::Blink, &white, &black)];
+
+    let on = apply(&array, &spans, 0.25);
+    assert!(on.iter().all(|(_, _, c)| *c == white));
+
+    let off = apply(&array, &spans, 1.75);
+    for (x, _, c) in off.iter() {
+        let expected = if x == 1 { &black } else { &white };
+        assert_eq!(c, expected, "x {}", x);
+    }
+}
+
+#[test]
+fn test_invert_blink() {
+    let black = Color::new(0u8, 0u8, 0u8, 255u8);
+    let white = Color::new(255u8, 255u8, 255u8, 255u8);
+    let mut array = Array::new(2);
+    for _ in 0..3 {
+        array.add_line(vec![white.clone(), black.clone()]);
+    }
+    let spans = [span(EffectKind::InvertBlink, &white, &black)];
+
+    let off = apply(&array, &spans, 0.5);
+    assert_eq!(
+        off.get_line(1).unwrap(),
+        &vec![black.clone(), white.clone()]
+    );
+    assert_eq!(off.get_line(0).unwrap(), &vec![white, black]);
+}
+
+#[test]
+fn test_apply_clips_spans() {
+    let black = Color::new(0u8, 0u8, 0u8, 255u8);
+    let white = Color::new(255u8, 255u8, 255u8, 255u8);
+    let array = Array::filled(2, 1, &white);
+    let mut blink = span(EffectKind::Blink, &white, &black);
+    blink.end = 5;
+
+    let off = apply(&array, &[blink], 0.75);
+    assert_eq!(off.get_line(0).unwrap(), &vec![white]);
+    assert_eq!(off.get_line(1).unwrap(), &vec![black]);
+}
+
+#[test]
+fn test_cycle_frames() {
+    let black = Color::new(0u8, 0u8, 0u8, 255u8);
+    let with_period = |period| {
+        let mut span = span(EffectKind::Blink, &black, &black);
+        span.effect.period = period;
+        span
+    };
+
+    assert_eq!(cycle_frames(&[], 10.0).unwrap(), 1);
+    assert_eq!(cycle_frames(&[with_period(0.4)], 10.0).unwrap(), 4);
+    assert_eq!(
+        cycle_frames(&[with_period(0.4), with_period(0.6)], 10.0).unwrap(),
+        12
+    );
+    assert!(cycle_frames(&[with_period(9.973), with_period(9.967)], 1000.0).is_err());
+}
@@ -0,0 +1,241 @@
+#[cfg(test)]
+mod test;
+
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+use failure::format_err;
+
+use crate::frame::{delays, Frame};
+use crate::model::array::Array;
+use crate::model::color::Color;
+use crate::model::Imageable;
+use crate::result::Result;
+use crate::source::{ByteOrder, Export, PixelFormat, Scan, Wiring};
+use crate::wiring::led_order;
+
+/// How exported data is written.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Container {
+    Binary,
+    C,
+    Rust,
+}
+
+/// Saves the logical frames in the layout of `export`.
+pub fn save_export(
+    path: impl AsRef<Path>,
+    frames: &[Frame],
+    fps: Option<f32>,
+    export: &Export,
+    wiring: Option<&Wiring>,
+    container: Container,
+) -> Result<()> {
+    let data = encode(frames, fps, export, wiring)?;
+    let first = &frames[0].array;
+    let count = if export.header() {
+        frames.len()
+    } else {
+        frames.iter().map(|frame| frame.hold).sum()
+    };
+    let comment = format!(
+        "{}x{}, {} frames, {:?}",
+        first.width(),
+        first.height(),
+        count,
+        export.pixel()
+    );
+
+    match container {
+        Container::Binary => fs::write(path, data)?,
+        Container::C => fs::write(path, c_source(&data, export.name(), &comment))?,
+        Container::Rust => fs::write(path, rust_source(&data, export.name(), &comment))?,
+    }
+
+    Ok(())
+}
+
+/// Encodes frames, each once with the header, which keeps the holds as delays.
+/// Without the header, a frame is repeated for every frame period it is held.
+///
+/// The header has the width, the height and the frame count, then the delay of each frame
+/// in milliseconds, all as 16 bit values. Pixels follow `wiring` if given, otherwise the scan
+/// of `export`.
+pub fn encode(
+    frames: &[Frame],
+    fps: Option<f32>,
+    export: &Export,
+    wiring: Option<&Wiring>,
+) -> Result<Vec<u8>> {
+    let first = frames
+        .first()
+        .ok_or_else(|| format_err!("no frames to export"))?;
+    let (width, height) = (first.array.width(), first.array.height());
+    let order = export.byte_order();
+
+    let leds = match wiring {
+        Some(_) if is_mono(export.pixel()) => {
+            return Err(format_err!(
+                "wiring cannot be used with {:?} pixels",
+                export.pixel()
+            ))
+        }
+        Some(wiring) => led_order(wiring, width, height)?,
+        None => scan(width, height, export.scan()),
+    };
+
+    let mut data = Vec::new();
+
+    if export.header() {
+        if frames.len() > 1 && fps.is_none() {
+            return Err(format_err!("export of an animation requires meta.fps"));
+        }
+
+        for value in &[width, height, frames.len()] {
+            if *value > u16::max_value() as usize {
+                return Err(format_err!("{} does not fit in the header", value));
+            }
+            put_u16(&mut data, *value as u16, order);
+        }
+
+        // Delays are in milliseconds.
+        for delay in delays(frames, fps, 1000.0) {
+            put_u16(&mut data, delay, order);
+        }
+    }
+
+    for frame in frames {
+        if frame.array.dim() != (width, height) {
+            return Err(format_err!("frame size differs from the first frame"));
+        }
+        let repeat = if export.header() { 1 } else { frame.hold };
+        for _ in 0..repeat {
+            encode_frame(&mut data, &frame.array, &leds, export);
+        }
+    }
+
+    Ok(data)
+}
+
+fn is_mono(pixel: PixelFormat) -> bool {
+    matches!(pixel, PixelFormat::MonoRows | PixelFormat::MonoColumns)
+}
+
+/// Appends the pixels at `leds`, or every pixel for the packed 1 bit formats.
+fn encode_frame(data: &mut Vec<u8>, array: &Array, leds: &[(usize, usize)], export: &Export) {
+    let (width, height) = array.dim();
+    let on = |x, y| luma(&array.get(x, y)) >= export.threshold();
+
+    match export.pixel() {
+        PixelFormat::Rgb888 => {
+            for &(x, y) in leds {
+                data.extend(&array.get(x, y).rgb());
+            }
+        }
+        PixelFormat::Rgb565 => {
+            for &(x, y) in leds {
+                let [r, g, b] = array.get(x, y).rgb();
+                let value =
+                    (u16::from(r) >> 3) << 11 | (u16::from(g) >> 2) << 5 | u16::from(b) >> 3;
+                put_u16(data, value, export.byte_order());
+            }
+        }
+        PixelFormat::Gray4 => {
+            let levels = leds
+                .iter()
+                .map(|&(x, y)| (luma(&array.get(x, y)) * 15.0).round() as u8)
+                .collect::<Vec<_>>();
+            for pair in levels.chunks(2) {
+                data.push(pair[0] << 4 | pair.get(1).cloned().unwrap_or(0));
+            }
+        }
+        PixelFormat::MonoRows => {
+            for y in 0..height {
+                for start in (0..width).step_by(8) {
+                    let byte = (start..width.min(start + 8))
+                        .filter(|&x| on(x, y))
+                        .fold(0, |byte, x| byte | 0x80 >> (x - start));
+                    data.push(byte);
+                }
+            }
+        }
+        PixelFormat::MonoColumns => {
+            for x in 0..width {
+                for start in (0..height).step_by(8) {
+                    let byte = (start..height.min(start + 8))
+                        .filter(|&y| on(x, y))
+                        .fold(0, |byte, y| byte | 1 << (y - start));
+                    data.push(byte);
+                }
+            }
+        }
+    }
+}
+
+/// Coordinates of a `width` x `height` area in the order of `scan`.
+pub fn scan(width: usize, height: usize, scan: Scan) -> Vec<(usize, usize)> {
+    let serpentine = |i: usize, j: usize, len: usize| if i & 1 == 0 { j } else { len - 1 - j };
+
+    match scan {
+        Scan::Rows => (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .collect(),
+        Scan::Columns => (0..width)
+            .flat_map(|x| (0..height).map(move |y| (x, y)))
+            .collect(),
+        Scan::SerpentineRows => (0..height)
+            .flat_map(|y| (0..width).map(move |x| (serpentine(y, x, width), y)))
+            .collect(),
+        Scan::SerpentineColumns => (0..width)
+            .flat_map(|x| (0..height).map(move |y| (x, serpentine(x, y, height))))
+            .collect(),
+    }
+}
+
+fn put_u16(data: &mut Vec<u8>, value: u16, order: ByteOrder) {
+    match order {
+        ByteOrder::Little => data.extend(&value.to_le_bytes()),
+        ByteOrder::Big => data.extend(&value.to_be_bytes()),
+    }
+}
+
+/// BT.601 luma in 0.0 to 1.0.
+fn luma(color: &Color) -> f32 {
+    (0.299 * color.r + 0.587 * color.g + 0.114 * color.b) * color.a
+}
+
+fn hex_lines(data: &[u8], indent: &str) -> String {
+    let mut lines = String::new();
+
+    for chunk in data.chunks(16) {
+        let bytes = chunk
+            .iter()
+            .map(|b| format!("0x{:02x},", b))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let _ = writeln!(lines, "{}{}", indent, bytes);
+    }
+
+    lines
+}
+
+fn c_source(data: &[u8], name: &str, comment: &str) -> String {
+    format!(
+        "/* {} */\nconst unsigned char {}[{}] = {{\n{}}};\n",
+        comment,
+        name,
+        data.len(),
+        hex_lines(data, "    ")
+    )
+}
+
+fn rust_source(data: &[u8], name: &str, comment: &str) -> String {
+    format!(
+        "// {}\npub static {}: [u8; {}] = [\n{}];\n",
+        comment,
+        name.to_uppercase(),
+        data.len(),
+        hex_lines(data, "    ")
+    )
+}
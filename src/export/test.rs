@@ -0,0 +1,123 @@
+use super::*;
+
+fn array(rows: &[&str]) -> Array {
+    let on = Color::new(255u8, 255u8, 255u8, 255u8);
+    let off = Color::new(0u8, 0u8, 0u8, 255u8);
+
+    let mut array = Array::new(rows.len());
+    for x in 0..rows[0].len() {
+        let line = rows
+            .iter()
+            .map(|row| {
+                if row.as_bytes()[x] == b'#' {
+                    on.clone()
+                } else {
+                    off.clone()
+                }
+            })
+            .collect();
+        array.add_line(line);
+    }
+    array
+}
+
+fn export(yaml: &str) -> Export {
+    serde_yaml::from_str(yaml).unwrap()
+}
+
+#[test]
+fn test_header() {
+    let frames = vec![
+        Frame {
+            array: array(&["#."]),
+            hold: 3,
+        },
+        Frame {
+            array: array(&[".#"]),
+            hold: 1,
+        },
+    ];
+
+    let data = encode(
+        &frames,
+        Some(10.0),
+        &export("{pixel: mono-rows, byte-order: big}"),
+        None,
+    )
+    .unwrap();
+    assert_eq!(data, vec![0, 2, 0, 1, 0, 2, 1, 44, 0, 100, 0x80, 0x40]);
+}
+
+#[test]
+fn test_mono_columns() {
+    let frames = vec![Frame {
+        array: array(&["#.", ".#", "..", "..", "..", "..", "..", "..", "##"]),
+        hold: 1,
+    }];
+
+    let data = encode(
+        &frames,
+        None,
+        &export("{pixel: mono-columns, header: false}"),
+        None,
+    )
+    .unwrap();
+    assert_eq!(data, vec![0x01, 0x01, 0x02, 0x01]);
+}
+
+#[test]
+fn test_rgb565_serpentine() {
+    let frames = vec![Frame {
+        array: array(&["#.", "#."]),
+        hold: 1,
+    }];
+
+    let data = encode(
+        &frames,
+        None,
+        &export("{pixel: rgb565, scan: serpentine-rows, header: false}"),
+        None,
+    )
+    .unwrap();
+    assert_eq!(data, vec![0xff, 0xff, 0, 0, 0, 0, 0xff, 0xff]);
+}
+
+#[test]
+fn test_gray4() {
+    let frames = vec![Frame {
+        array: array(&["#.#"]),
+        hold: 1,
+    }];
+
+    let data = encode(
+        &frames,
+        None,
+        &export("{pixel: gray4, header: false}"),
+        None,
+    )
+    .unwrap();
+    assert_eq!(data, vec![0xf0, 0xf0]);
+}
+
+#[test]
+fn test_no_header_repeats_holds() {
+    let frames = vec![
+        Frame {
+            array: array(&["#."]),
+            hold: 3,
+        },
+        Frame {
+            array: array(&[".#"]),
+            hold: 1,
+        },
+    ];
+
+    let data = encode(
+        &frames,
+        Some(10.0),
+        &export("{pixel: mono-rows, header: false}"),
+        None,
+    )
+    .unwrap();
+    assert_eq!(data, vec![0x80, 0x80, 0x80, 0x40]);
+}
@@ -1,6 +1,8 @@
 use std::collections::hash_map::Entry;
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+#[cfg(test)]
+use std::sync::Once;
 
 use crate::result::Result;
 
@@ -43,6 +45,40 @@ impl FontDir {
     }
 }
 
+#[derive(Debug, Clone)]
+pub struct FontSet {
+    search_path: PathBuf,
+    default: String,
+    fonts: HashMap<String, FontDir>,
+}
+
+impl FontSet {
+    pub fn new(search_path: impl AsRef<Path>, default: &str) -> Result<FontSet> {
+        let mut font_set = FontSet {
+            search_path: search_path.as_ref().to_path_buf(),
+            default: default.to_string(),
+            fonts: HashMap::new(),
+        };
+
+        let _ = font_set.get(None)?;
+        Ok(font_set)
+    }
+
+    pub fn get(&mut self, name: Option<&str>) -> Result<&mut FontDir> {
+        let name = name.unwrap_or(&self.default).to_string();
+
+        if let Entry::Vacant(vacant) = self.fonts.entry(name.clone()) {
+            vacant.insert(FontDir::new(&self.search_path, &name)?);
+        }
+
+        Ok(self.fonts.get_mut(&name).unwrap())
+    }
+
+    pub fn height(&self) -> usize {
+        self.fonts.get(&self.default).unwrap().height()
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct CharImage {
     ysize: usize,
@@ -88,18 +124,33 @@ impl CharImage {
         Ok(cimg)
     }
 
-    pub fn get(&self, x: usize, y: usize) -> bool {
-        if y >= self.ysize {
-            panic!("y: out of index");
-        }
-
-        let &current = self.arr.get(x).expect("x: out of index");
-        let mask = 1 << y;
-
-        current & mask != 0
+    pub fn column(&self, x: usize) -> u64 {
+        *self.arr.get(x).expect("x: out of index")
     }
 
     pub fn dim(&self) -> (usize, usize) {
         (self.arr.len(), self.ysize)
     }
 }
+
+/// A font set whose default font `test` draws each lowercase letter and digit
+/// as a single lit dot, and a space as an unlit one.
+#[cfg(test)]
+pub fn test_font_set() -> FontSet {
+    static WRITE: Once = Once::new();
+    let dir = std::env::temp_dir().join(format!("led-image-fonts-{}", std::process::id()));
+
+    WRITE.call_once(|| {
+        let chars = dir.join("test").join("chars");
+        std::fs::create_dir_all(&chars).unwrap();
+
+        for ch in " abcdefghijklmnopqrstuvwxyz0123456789".chars() {
+            let luma = if ch == ' ' { 255 } else { 0 };
+            image::GrayImage::from_pixel(1, 1, image::Luma([luma]))
+                .save(chars.join(format!("{:04x}.png", ch as u16)))
+                .unwrap();
+        }
+    });
+
+    FontSet::new(&dir, "test").unwrap()
+}
@@ -0,0 +1,87 @@
+#[cfg(test)]
+mod test;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use failure::format_err;
+use rayon::prelude::*;
+
+use crate::model::array::Array;
+use crate::model::dot_array::DotArray;
+use crate::result::Result;
+use crate::save;
+
+/// Logical LED states of a frame, shown for `hold` frame periods.
+#[derive(Debug, Clone)]
+pub struct Frame {
+    pub array: Array,
+    pub hold: usize,
+}
+
+/// Number of frames lasting `duration` seconds at `fps`. Fails unless it is at least one.
+pub fn frame_count(duration: f32, fps: f32) -> Result<usize> {
+    if !duration.is_finite() || duration <= 0.0 {
+        return Err(format_err!("duration must be positive, got {}", duration));
+    }
+
+    match (duration * fps).round() as usize {
+        0 => Err(format_err!(
+            "duration {} is shorter than a frame at {} fps",
+            duration,
+            fps
+        )),
+        count => Ok(count),
+    }
+}
+
+/// Display time of each frame in `1 / unit` seconds, or 0 without `fps`.
+///
+/// Times are rounded on the elapsed time so that they do not drift.
+pub fn delays(frames: &[Frame], fps: Option<f32>, unit: f32) -> Vec<u16> {
+    let mut elapsed = 0;
+    let mut shown = 0;
+
+    frames
+        .iter()
+        .map(|frame| {
+            elapsed += frame.hold;
+            let until = fps
+                .map(|fps| (elapsed as f32 * unit / fps).round() as usize)
+                .unwrap_or(0);
+            let delay = until - shown;
+            shown = until;
+            delay.min(u16::max_value() as usize) as u16
+        })
+        .collect()
+}
+
+/// Saves frames as numbered PNG files with the dot of `dot_arr`.
+/// A held frame is saved repeatedly.
+pub fn save_frames(dir_path: impl AsRef<Path>, frames: &[Frame], dot_arr: &DotArray) -> Result<()> {
+    let _ = fs::remove_dir_all(&dir_path);
+    fs::create_dir_all(&dir_path)?;
+
+    let mut index = 0;
+    let paths: Vec<(Vec<PathBuf>, &Frame)> = frames
+        .iter()
+        .map(|frame| {
+            let paths = (index..index + frame.hold)
+                .map(|i| dir_path.as_ref().join(format!("{:08}.png", i)))
+                .collect();
+            index += frame.hold;
+            (paths, frame)
+        })
+        .collect();
+
+    paths
+        .into_par_iter()
+        .filter(|(paths, _)| !paths.is_empty())
+        .try_for_each(|(paths, frame)| {
+            save::save_image(&paths[0], &dot_arr.with_array(frame.array.clone()))?;
+            for path in &paths[1..] {
+                fs::copy(&paths[0], path)?;
+            }
+            Ok(())
+        })
+}
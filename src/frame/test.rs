@@ -0,0 +1,19 @@
+use super::*;
+
+fn held(holds: &[usize]) -> Vec<Frame> {
+    holds
+        .iter()
+        .map(|&hold| Frame {
+            array: Array::new(1),
+            hold,
+        })
+        .collect()
+}
+
+#[test]
+fn test_delays_do_not_drift() {
+    // 1/3 second is 33.3 centiseconds, so every third frame takes the remainder.
+    assert_eq!(delays(&held(&[1, 1, 1]), Some(3.0), 100.0), [33, 34, 33]);
+    assert_eq!(delays(&held(&[2, 1]), Some(10.0), 1000.0), [200, 100]);
+    assert_eq!(delays(&held(&[1, 1]), None, 100.0), [0, 0]);
+}
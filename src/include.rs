@@ -0,0 +1,325 @@
+#[cfg(test)]
+mod test;
+
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use failure::format_err;
+use serde::de::DeserializeOwned;
+use serde_yaml::{Mapping, Value};
+use yaml_rust::parser::{Event, MarkedEventReceiver, Parser};
+use yaml_rust::scanner::{Marker, TScalarStyle, TokenType};
+use yaml_rust::Yaml;
+
+use crate::result::Result;
+
+/// Loads a YAML file, replacing every `!include path` scalar with the content of `path`.
+///
+/// Relative paths are resolved against the directory of the including file.
+/// Returns the document with where each of its nodes was written.
+pub fn load(path: impl AsRef<Path>) -> Result<(Value, Locations)> {
+    load_nested(path.as_ref(), &mut Vec::new())
+}
+
+fn load_nested(path: &Path, stack: &mut Vec<PathBuf>) -> Result<(Value, Locations)> {
+    let canonical = fs::canonicalize(path)
+        .map_err(|e| format_err!("cannot open '{}': {}", path.display(), e))?;
+
+    if stack.contains(&canonical) {
+        return Err(format_err!(
+            "recursive include: {}",
+            stack
+                .iter()
+                .chain(Some(&canonical))
+                .map(|p| p.display().to_string())
+                .collect::<Vec<_>>()
+                .join(" -> ")
+        ));
+    }
+
+    let src = fs::read_to_string(path)?;
+
+    let mut loader = Loader {
+        stack: Vec::new(),
+        anchors: HashMap::new(),
+        doc: None,
+        locations: Locations::default(),
+    };
+    Parser::new(src.chars())
+        .load(&mut loader, false)
+        .map_err(|e| format_err!("{}: {}", path.display(), e))?;
+
+    stack.push(canonical);
+    let base = path.parent().unwrap_or_else(|| Path::new(""));
+    let mut doc = loader.doc.unwrap_or(Value::Null);
+    let mut locations = loader.locations;
+    let result = resolve(&mut doc, "", &mut locations, base, stack);
+    stack.pop();
+
+    result
+        .map(|_| (doc, locations))
+        .map_err(|e| format_err!("{}: {}", path.display(), e))
+}
+
+/// Prefix of the placeholder left by the loader where an `!include` scalar was.
+/// The placeholder is `<prefix><line>:<path>`.
+const INCLUDE_MARK: &str = "\u{0}include:";
+
+fn resolve(
+    value: &mut Value,
+    path: &str,
+    locations: &mut Locations,
+    base: &Path,
+    stack: &mut Vec<PathBuf>,
+) -> Result<()> {
+    match value {
+        Value::String(s) if s.starts_with(INCLUDE_MARK) => {
+            let mut parts = s.trim_start_matches(INCLUDE_MARK).splitn(2, ':');
+            let line = parts.next().unwrap().to_string();
+            let name = parts.next().unwrap().to_string();
+
+            let file = base.join(&name);
+            let (doc, mut included) =
+                load_nested(&file, stack).map_err(|e| format_err!("line {}: {}", line, e))?;
+            for location in included.0.values_mut() {
+                location.file.get_or_insert_with(|| file.clone());
+            }
+
+            *value = doc;
+            locations.take(path);
+            locations.put(path, included);
+        }
+        Value::Sequence(seq) => {
+            for (i, v) in seq.iter_mut().enumerate() {
+                resolve(v, &join(path, &index(i)), locations, base, stack)?;
+            }
+        }
+        Value::Mapping(map) => {
+            for (k, v) in map.iter_mut() {
+                if let Value::String(k) = k {
+                    resolve(v, &join(path, k), locations, base, stack)?;
+                }
+            }
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+/// Where a node of a document was written.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Location {
+    /// The included file, or `None` for the loaded one.
+    file: Option<PathBuf>,
+    line: usize,
+    column: usize,
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {} column {}", self.line, self.column)?;
+        if let Some(ref file) = self.file {
+            write!(f, " of {}", file.display())?;
+        }
+        Ok(())
+    }
+}
+
+/// The locations of the nodes of a document, by their path such as `body[1].text`.
+#[derive(Debug, Clone, Default)]
+pub struct Locations(HashMap<String, Location>);
+
+impl Locations {
+    pub fn get(&self, path: &str) -> Option<&Location> {
+        self.0.get(path)
+    }
+
+    /// Removes the locations of the node at `path` and of its children,
+    /// and returns them with paths relative to it.
+    pub fn take(&mut self, path: &str) -> Locations {
+        let (taken, kept) = self
+            .0
+            .drain()
+            .partition::<HashMap<_, _>, _>(|(p, _)| relative(p, path).is_some());
+        self.0 = kept;
+
+        Locations(
+            taken
+                .into_iter()
+                .map(|(p, l)| (relative(&p, path).unwrap().to_string(), l))
+                .collect(),
+        )
+    }
+
+    /// Adds `locations`, relative to the node at `path`.
+    pub fn put(&mut self, path: &str, locations: Locations) {
+        for (p, l) in locations.0 {
+            self.0.insert(join(path, &p), l);
+        }
+    }
+
+    /// Deserializes `doc`, telling where the failing node was written.
+    pub fn deserialize<T: DeserializeOwned>(&self, doc: Value) -> Result<T> {
+        match serde_yaml::from_value(doc.clone()) {
+            Ok(value) => Ok(value),
+            Err(e) => Err(self.locate::<T>(&doc).unwrap_or_else(|| e.into())),
+        }
+    }
+
+    /// Deserializes `doc` again from YAML text to know the path of the failing node,
+    /// and replaces the position in that text with the recorded one.
+    fn locate<T: DeserializeOwned>(&self, doc: &Value) -> Option<failure::Error> {
+        let text = serde_yaml::to_string(doc).ok()?;
+        let error = serde_yaml::from_str::<T>(&text).err()?;
+        let at = error.location()?;
+
+        let message = error.to_string();
+        let suffix = format!(" at line {} column {}", at.line(), at.column());
+        let message = message.trim_end_matches(suffix.as_str());
+        let (path, info) = match message.find(": ") {
+            Some(i) if self.0.contains_key(&message[..i]) => (&message[..i], &message[i + 2..]),
+            _ => ("", message),
+        };
+        let location = self.get(path)?;
+
+        Some(if path.is_empty() {
+            format_err!("{} at {}", info, location)
+        } else {
+            format_err!("{}: {} at {}", path, info, location)
+        })
+    }
+}
+
+/// The path of the `i`th item of a sequence, relative to the sequence.
+pub fn index(i: usize) -> String {
+    format!("[{}]", i)
+}
+
+fn join(path: &str, child: &str) -> String {
+    if path.is_empty() || child.is_empty() || child.starts_with('[') {
+        format!("{}{}", path, child)
+    } else {
+        format!("{}.{}", path, child)
+    }
+}
+
+/// `path` relative to `base`, if it is `base` or one of its children.
+fn relative<'a>(path: &'a str, base: &str) -> Option<&'a str> {
+    if base.is_empty() {
+        return Some(path);
+    }
+
+    let rest = path.strip_prefix(base)?;
+    if rest.is_empty() || rest.starts_with('[') {
+        Some(rest)
+    } else {
+        rest.strip_prefix('.')
+    }
+}
+
+struct Loader {
+    stack: Vec<(Value, usize, Option<Value>)>,
+    anchors: HashMap<usize, Value>,
+    doc: Option<Value>,
+    locations: Locations,
+}
+
+impl Loader {
+    /// Records where the node starting at `mark` was written, unless it is a mapping key.
+    fn mark(&mut self, mark: Marker) {
+        let mut path = String::new();
+        for (value, _, key) in self.stack.iter() {
+            let child = match (value, key) {
+                (Value::Sequence(seq), _) => index(seq.len()),
+                (Value::Mapping(_), Some(Value::String(key))) => key.clone(),
+                _ => return,
+            };
+            path = join(&path, &child);
+        }
+
+        let location = Location {
+            file: None,
+            line: mark.line(),
+            column: mark.col() + 1,
+        };
+        self.locations.0.insert(path, location);
+    }
+
+    fn insert(&mut self, value: Value, anchor: usize) {
+        if anchor > 0 {
+            self.anchors.insert(anchor, value.clone());
+        }
+
+        match self.stack.last_mut() {
+            None => self.doc = Some(value),
+            Some((Value::Sequence(seq), _, _)) => seq.push(value),
+            Some((Value::Mapping(map), _, key)) => {
+                if let Some(k) = key.take() {
+                    map.insert(k, value);
+                } else {
+                    *key = Some(value);
+                }
+            }
+            Some(_) => unreachable!(),
+        }
+    }
+}
+
+impl MarkedEventReceiver for Loader {
+    fn on_event(&mut self, ev: Event, mark: Marker) {
+        match ev {
+            Event::SequenceStart(_)
+            | Event::MappingStart(_)
+            | Event::Alias(_)
+            | Event::Scalar(..) => self.mark(mark),
+            _ => {}
+        }
+
+        match ev {
+            Event::SequenceStart(anchor) => {
+                self.stack.push((Value::Sequence(Vec::new()), anchor, None));
+            }
+            Event::MappingStart(anchor) => {
+                self.stack
+                    .push((Value::Mapping(Mapping::new()), anchor, None));
+            }
+            Event::SequenceEnd | Event::MappingEnd => {
+                let (value, anchor, _) = self.stack.pop().unwrap();
+                self.insert(value, anchor);
+            }
+            Event::Alias(anchor) => {
+                let value = self.anchors.get(&anchor).cloned().unwrap_or(Value::Null);
+                self.insert(value, 0);
+            }
+            Event::Scalar(v, style, anchor, tag) => {
+                let value = match tag {
+                    Some(TokenType::Tag(ref handle, ref suffix))
+                        if handle == "!" && suffix == "include" =>
+                    {
+                        Value::String(format!("{}{}:{}", INCLUDE_MARK, mark.line(), v))
+                    }
+                    Some(TokenType::Tag(ref handle, ref suffix))
+                        if handle == "!!" && suffix == "str" =>
+                    {
+                        Value::String(v)
+                    }
+                    _ if style != TScalarStyle::Plain => Value::String(v),
+                    _ => scalar(v),
+                };
+                self.insert(value, anchor);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Keeps a plain scalar as a string, as every field parses it to its own type, unless it is null.
+fn scalar(v: String) -> Value {
+    match Yaml::from_str(&v) {
+        Yaml::Null => Value::Null,
+        _ => Value::String(v),
+    }
+}
@@ -0,0 +1,125 @@
+use std::process;
+
+use super::*;
+
+/// Writes `files` into a fresh directory and returns its path.
+fn write_files(name: &str, files: &[(&str, &str)]) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("led-image-include-{}-{}", process::id(), name));
+    let _ = fs::remove_dir_all(&dir);
+
+    for (path, content) in files {
+        let path = dir.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    dir
+}
+
+#[test]
+fn test_include_relative() {
+    let dir = write_files(
+        "relative",
+        &[
+            ("main.yaml", "meta: !include parts/meta.yaml\nbody: []\n"),
+            (
+                "parts/meta.yaml",
+                "font: test\ncolors: !include colors.yaml\n",
+            ),
+            ("parts/colors.yaml", "red: '#ff0000'\n"),
+        ],
+    );
+
+    let (doc, locations) = load(dir.join("main.yaml")).unwrap();
+    let to_be: Value = serde_yaml::from_str(
+        "
+meta:
+  font: test
+  colors: {red: '#ff0000'}
+body: []
+",
+    )
+    .unwrap();
+    assert_eq!(doc, to_be);
+
+    let body = locations.get("body").unwrap();
+    assert_eq!(body.to_string(), "line 2 column 7");
+    let red = locations.get("meta.colors.red").unwrap();
+    assert_eq!(
+        red.to_string(),
+        format!(
+            "line 1 column 6 of {}",
+            dir.join("parts/colors.yaml").display()
+        )
+    );
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_include_recursive() {
+    let dir = write_files(
+        "recursive",
+        &[
+            ("a.yaml", "body: !include b.yaml\n"),
+            ("b.yaml", "- !include a.yaml\n"),
+        ],
+    );
+
+    let err = load(dir.join("a.yaml")).unwrap_err().to_string();
+    assert!(err.contains("recursive include"), "{}", err);
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_include_missing() {
+    let dir = write_files("missing", &[("a.yaml", "body: !include none.yaml\n")]);
+
+    let err = load(dir.join("a.yaml")).unwrap_err().to_string();
+    assert!(err.contains("line 1"), "{}", err);
+    assert!(err.contains("none.yaml"), "{}", err);
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_scalar() {
+    assert_eq!(scalar("007".to_string()), Value::String("007".to_string()));
+    assert_eq!(
+        scalar("1.10".to_string()),
+        Value::String("1.10".to_string())
+    );
+    assert_eq!(
+        scalar("true".to_string()),
+        Value::String("true".to_string())
+    );
+    assert_eq!(scalar("~".to_string()), Value::Null);
+}
+
+#[test]
+fn test_locations() {
+    let mut locations = Locations::default();
+    for (path, line) in &[
+        ("body", 1),
+        ("body[1]", 2),
+        ("body[1].text", 3),
+        ("body[10]", 4),
+    ] {
+        let location = Location {
+            file: None,
+            line: *line,
+            column: 1,
+        };
+        locations.0.insert(path.to_string(), location);
+    }
+
+    let mut item = locations.take("body[1]");
+    assert_eq!(item.get("").unwrap().line, 2);
+    assert_eq!(item.get("text").unwrap().line, 3);
+    assert!(locations.get("body[10]").is_some());
+
+    locations.put("body[0]", item.take(""));
+    assert_eq!(locations.get("body[0]").unwrap().line, 2);
+    assert_eq!(locations.get("body[0].text").unwrap().line, 3);
+}
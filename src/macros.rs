@@ -0,0 +1,175 @@
+#[cfg(test)]
+mod test;
+
+use std::collections::HashMap;
+
+use failure::format_err;
+use serde_derive::Deserialize;
+use serde_yaml::Value;
+
+use crate::include::{self, Locations};
+use crate::result::Result;
+use crate::template;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Macro {
+    #[serde(default)]
+    params: HashMap<String, Option<Value>>,
+    body: Vec<Value>,
+    #[serde(skip)]
+    locations: Locations,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Call {
+    name: String,
+    #[serde(default)]
+    args: HashMap<String, Value>,
+}
+
+/// Removes the top-level `macros` map from `doc` and expands every `macro` command with it.
+///
+/// A parameter is referred as `${name}` in the macro body. A `~` default makes it required.
+/// `locations` follow the moved nodes; expanded ones are located in the macro body.
+pub fn expand(doc: &mut Value, locations: &mut Locations) -> Result<()> {
+    let macros = match doc {
+        Value::Mapping(map) => map.remove(&Value::String("macros".to_string())),
+        _ => None,
+    };
+
+    let mut macro_locations = locations.take("macros");
+    let mut macros: HashMap<String, Macro> = match macros {
+        Some(m) => macro_locations
+            .deserialize(m)
+            .map_err(|e| format_err!("macros: {}", e))?,
+        None => HashMap::new(),
+    };
+
+    for (name, def) in macros.iter_mut() {
+        def.locations = macro_locations.take(&format!("{}.body", name));
+    }
+
+    expand_value(doc, locations, &macros, &mut Vec::new())
+}
+
+fn expand_value(
+    value: &mut Value,
+    locations: &mut Locations,
+    macros: &HashMap<String, Macro>,
+    stack: &mut Vec<String>,
+) -> Result<()> {
+    match value {
+        Value::Sequence(seq) => {
+            let mut expanded = Vec::with_capacity(seq.len());
+            let mut moved = Locations::default();
+
+            for (i, mut item) in seq.drain(..).enumerate() {
+                let mut item_locations = locations.take(&include::index(i));
+
+                match call(&item)? {
+                    Some(call) => {
+                        for (item, item_locations) in expand_call(call, macros, stack)? {
+                            moved.put(&include::index(expanded.len()), item_locations);
+                            expanded.push(item);
+                        }
+                    }
+                    None => {
+                        expand_value(&mut item, &mut item_locations, macros, stack)?;
+                        moved.put(&include::index(expanded.len()), item_locations);
+                        expanded.push(item);
+                    }
+                }
+            }
+
+            locations.put("", moved);
+            *seq = expanded;
+        }
+        Value::Mapping(map) => {
+            for (k, v) in map.iter_mut() {
+                match k {
+                    Value::String(k) => {
+                        let mut child_locations = locations.take(k);
+                        expand_value(v, &mut child_locations, macros, stack)?;
+                        locations.put(k, child_locations);
+                    }
+                    _ => expand_value(v, &mut Locations::default(), macros, stack)?,
+                }
+            }
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+fn call(item: &Value) -> Result<Option<Call>> {
+    let map = match item {
+        Value::Mapping(map) if map.len() == 1 => map,
+        _ => return Ok(None),
+    };
+
+    match map.get(&Value::String("macro".to_string())) {
+        Some(v) => Ok(Some(
+            serde_yaml::from_value(v.clone()).map_err(|e| format_err!("macro: {}", e))?,
+        )),
+        None => Ok(None),
+    }
+}
+
+fn expand_call(
+    call: Call,
+    macros: &HashMap<String, Macro>,
+    stack: &mut Vec<String>,
+) -> Result<Vec<(Value, Locations)>> {
+    let def = macros
+        .get(&call.name)
+        .ok_or_else(|| format_err!("unknown macro: '{}'", call.name))?;
+
+    if stack.contains(&call.name) {
+        return Err(format_err!(
+            "recursive macro: {} -> {}",
+            stack.join(" -> "),
+            call.name
+        ));
+    }
+
+    if let Some(name) = call.args.keys().find(|n| !def.params.contains_key(*n)) {
+        return Err(format_err!(
+            "macro '{}' has no parameter '{}'",
+            call.name,
+            name
+        ));
+    }
+
+    let mut args = HashMap::new();
+    for (name, default) in def.params.iter() {
+        let value = call
+            .args
+            .get(name)
+            .or(default.as_ref())
+            .ok_or_else(|| format_err!("macro '{}' needs parameter '{}'", call.name, name))?;
+        let value = template::scalar_string(value)
+            .map_err(|e| format_err!("macro '{}' parameter '{}': {}", call.name, name, e))?;
+        args.insert(name.to_string(), value);
+    }
+
+    let mut body = Value::Sequence(def.body.clone());
+    let mut locations = def.locations.clone();
+    template::substitute_value(&mut body, &|name| args.get(name).cloned());
+
+    stack.push(call.name.clone());
+    let result = expand_value(&mut body, &mut locations, macros, stack);
+    stack.pop();
+    result.map_err(|e| format_err!("in macro '{}': {}", call.name, e))?;
+
+    match body {
+        Value::Sequence(seq) => Ok(seq
+            .into_iter()
+            .enumerate()
+            .map(|(i, item)| (item, locations.take(&include::index(i))))
+            .collect()),
+        _ => unreachable!(),
+    }
+}
@@ -0,0 +1,80 @@
+use super::*;
+
+fn expanded(yaml: &str) -> Result<Value> {
+    let mut doc: Value = serde_yaml::from_str(yaml).unwrap();
+    expand(&mut doc, &mut Locations::default()).map(|_| doc)
+}
+
+#[test]
+fn test_expand_params() {
+    let doc = expanded(
+        "
+macros:
+  station:
+    params: {name: ~, gap: 4}
+    body:
+      - text: {c: 'Next: ${name}'}
+      - space: {w: '${gap}'}
+body:
+  - macro: {name: station, args: {name: Shinjuku}}
+  - macro: {name: station, args: {name: Yoyogi, gap: 2}}
+",
+    )
+    .unwrap();
+
+    let to_be: Value = serde_yaml::from_str(
+        "
+body:
+  - text: {c: 'Next: Shinjuku'}
+  - space: {w: '4'}
+  - text: {c: 'Next: Yoyogi'}
+  - space: {w: '2'}
+",
+    )
+    .unwrap();
+    assert_eq!(doc, to_be);
+}
+
+#[test]
+fn test_expand_nested() {
+    let doc = expanded(
+        "
+macros:
+  inner:
+    params: {c: ~}
+    body: [{text: {c: '${c}!'}}]
+  outer:
+    body: [{macro: {name: inner, args: {c: hi}}}]
+body:
+  - macro: {name: outer}
+",
+    )
+    .unwrap();
+
+    let to_be: Value = serde_yaml::from_str("body: [{text: {c: 'hi!'}}]").unwrap();
+    assert_eq!(doc, to_be);
+}
+
+#[test]
+fn test_expand_errors() {
+    let samples = vec![
+        ("body: [{macro: {name: none}}]", "unknown macro: 'none'"),
+        (
+            "{macros: {m: {params: {c: ~}, body: []}}, body: [{macro: {name: m}}]}",
+            "macro 'm' needs parameter 'c'",
+        ),
+        (
+            "{macros: {m: {body: []}}, body: [{macro: {name: m, args: {c: 1}}}]}",
+            "macro 'm' has no parameter 'c'",
+        ),
+        (
+            "{macros: {m: {body: [{macro: {name: m}}]}}, body: [{macro: {name: m}}]}",
+            "in macro 'm': recursive macro: m -> m",
+        ),
+    ];
+
+    for (yaml, message) in samples {
+        let err = expanded(yaml).unwrap_err();
+        assert_eq!(err.to_string(), message, "expanding '{}'", yaml);
+    }
+}
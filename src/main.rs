@@ -1,23 +1,59 @@
 mod builder;
+mod condense;
+mod effect;
+mod export;
 mod font;
+mod frame;
+mod include;
 mod load;
+mod macros;
+mod markup;
 mod model;
+mod play;
+mod render;
 mod result;
 mod save;
+mod save_apng;
+mod save_gif;
+mod save_svg;
+mod save_y4m;
 mod scroll;
 mod source;
+mod template;
+mod timeline;
+mod transition;
+mod typewriter;
+mod video;
+mod wiring;
+mod zone;
 
-use std::process::{Command, Stdio};
+use std::collections::HashMap;
+use std::path::PathBuf;
 
-use clap::{crate_authors, crate_description, crate_name, crate_version, App, Arg};
+use clap::{
+    crate_authors, crate_description, crate_name, crate_version, App, AppSettings, Arg, ArgMatches,
+    SubCommand,
+};
 use failure::format_err;
 
-use crate::builder::ArrayBuilder;
-use crate::font::FontDir;
+use crate::export::{save_export, Container};
+use crate::font::FontSet;
+use crate::frame::{save_frames, Frame};
+use crate::model::array::Array;
+use crate::model::color::Color;
 use crate::model::dot_array::DotArray;
+use crate::play::{play, PlayOptions, Protocol};
+use crate::render::Renderer;
 use crate::result::Result;
-use crate::scroll::save_scroll;
-use crate::source::Source;
+use crate::save_apng::save_apng;
+use crate::save_gif::save_gif;
+use crate::save_svg::{save_svg, save_svg_frames};
+use crate::save_y4m::save_y4m;
+use crate::scroll::scroll_frames;
+use crate::source::{Metadata, Source};
+use crate::timeline::timeline_frames;
+use crate::video::{encode_video, VideoOptions};
+use crate::zone::zone_frames;
 
 fn main() {
     if let Err(err) = wrapped_main() {
@@ -31,6 +67,7 @@ fn wrapped_main() -> Result<()> {
         .author(crate_authors!(", "))
         .version(crate_version!())
         .about(crate_description!())
+        .setting(AppSettings::SubcommandsNegateReqs)
         .arg(
             Arg::with_name("output")
                 .short("o")
@@ -47,27 +84,179 @@ fn wrapped_main() -> Result<()> {
             Arg::with_name("ffmpeg")
                 .long("ffmpeg")
                 .takes_value(true)
-                .help("Generate mp4 with specified fps"),
+                .help("Generate mp4 with specified fps, overriding meta.fps"),
+        )
+        .arg(
+            Arg::with_name("ffmpeg-bin")
+                .long("ffmpeg-bin")
+                .takes_value(true)
+                .value_name("PATH")
+                .default_value("ffmpeg")
+                .help("ffmpeg executable"),
+        )
+        .arg(
+            Arg::with_name("codec")
+                .long("codec")
+                .takes_value(true)
+                .default_value("libx264")
+                .help("Video codec of ffmpeg"),
+        )
+        .arg(
+            Arg::with_name("pix-fmt")
+                .long("pix-fmt")
+                .takes_value(true)
+                .default_value("yuv420p")
+                .help("Pixel format of the video"),
+        )
+        .arg(
+            Arg::with_name("crf")
+                .long("crf")
+                .takes_value(true)
+                .conflicts_with("bitrate")
+                .help("Constant rate factor of the video"),
+        )
+        .arg(
+            Arg::with_name("bitrate")
+                .long("bitrate")
+                .takes_value(true)
+                .help("Bitrate of the video, e.g. 2M"),
+        )
+        .arg(
+            Arg::with_name("container")
+                .long("container")
+                .takes_value(true)
+                .help("Container format of the video [default: mp4]"),
+        )
+        .arg(
+            Arg::with_name("video-output")
+                .long("video-output")
+                .takes_value(true)
+                .value_name("FILE")
+                .help("Video file name [default: <output>.<container>]"),
+        )
+        .arg(
+            Arg::with_name("no-frames")
+                .long("no-frames")
+                .requires("ffmpeg")
+                .help("Do not write the PNG frame directory when encoding a video"),
+        )
+        .arg(
+            Arg::with_name("format")
+                .long("format")
+                .takes_value(true)
+                .possible_values(&["png", "gif", "apng", "y4m", "svg", "bin", "c", "rust"])
+                .help("Output format [default: png]"),
+        )
+        .arg(
+            Arg::with_name("loop-count")
+                .long("loop-count")
+                .takes_value(true)
+                .value_name("COUNT")
+                .help("Number of times a gif or apng is played again, forever if not set"),
+        )
+        .arg(
+            Arg::with_name("logical")
+                .long("logical")
+                .help("Output one pixel per LED without rendering dots"),
+        )
+        .arg(
+            Arg::with_name("set")
+                .long("set")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1)
+                .value_name("NAME=VALUE")
+                .help("Set a template variable"),
+        )
+        .subcommand(
+            SubCommand::with_name("play")
+                .about("Send frames to LED controllers over the network")
+                .arg(Arg::with_name("source").required(true))
+                .arg(
+                    Arg::with_name("protocol")
+                        .long("protocol")
+                        .takes_value(true)
+                        .possible_values(&["e131", "artnet", "ddp", "drgb", "dnrgb"])
+                        .default_value("e131")
+                        .help("Network protocol"),
+                )
+                .arg(
+                    Arg::with_name("target")
+                        .long("target")
+                        .takes_value(true)
+                        .value_name("HOST[:PORT]")
+                        .required(true)
+                        .help("Address of the controller"),
+                )
+                .arg(
+                    Arg::with_name("universe")
+                        .long("universe")
+                        .takes_value(true)
+                        .help("First DMX universe [default: 1 for e131, 0 for artnet]"),
+                )
+                .arg(
+                    Arg::with_name("channel-order")
+                        .long("channel-order")
+                        .takes_value(true)
+                        .possible_values(&["rgb", "rbg", "grb", "gbr", "brg", "bgr"])
+                        .default_value("rgb")
+                        .help("Order of color channels of each LED"),
+                )
+                .arg(
+                    Arg::with_name("fps")
+                        .long("fps")
+                        .takes_value(true)
+                        .help("Frames per second, overriding meta.fps"),
+                )
+                .arg(
+                    Arg::with_name("loop")
+                        .long("loop")
+                        .help("Play the animation repeatedly until interrupted"),
+                )
+                .arg(
+                    Arg::with_name("set")
+                        .long("set")
+                        .takes_value(true)
+                        .multiple(true)
+                        .number_of_values(1)
+                        .value_name("NAME=VALUE")
+                        .help("Set a template variable"),
+                ),
         )
         .get_matches_safe()?;
 
-    let source_file = matches.value_of("source").unwrap();
-    let source = Source::load(source_file)?;
+    if let Some(matches) = matches.subcommand_matches("play") {
+        return play_main(matches);
+    }
 
-    let meta = source.metadata();
+    let source_file = matches.value_of("source").unwrap();
+    let source = Source::load(source_file, &parse_vars(&matches)?)?;
 
-    let font_dir = FontDir::new("./fonts", meta.font())?;
-    let color_set = meta.color_set()?;
-    let background = color_set.get("_background")?.clone();
+    let meta = match matches.value_of("ffmpeg") {
+        Some(fps) => source.metadata().with_fps(parse_fps(fps)?),
+        None => source.metadata(),
+    };
 
-    let mut builder = ArrayBuilder::new(color_set, font_dir);
-    for cmd in source.body() {
-        builder.process(cmd)?;
-    }
-    let arr = builder.finish();
+    let font_set = FontSet::new("./fonts", meta.font())?;
+    let renderer = Renderer::new(&meta, font_set)?;
 
-    let dot = load::load(meta.dot())?;
-    let dot_arr = DotArray::new(arr, dot);
+    // Exported data is made of LED states, so it never needs the dot.
+    let logical = matches.is_present("logical")
+        || matches!(
+            matches.value_of("format"),
+            Some("bin") | Some("c") | Some("rust")
+        );
+    let dot = if logical {
+        // A white dot of a single pixel leaves LED colors as they are.
+        let mut dot = Array::new(1);
+        dot.add_line(vec![Color::new(255u8, 255u8, 255u8, 255u8)]);
+        dot
+    } else {
+        let dot = meta
+            .dot()
+            .ok_or_else(|| format_err!("meta.dot is required unless --logical is set"))?;
+        load::load(dot)?
+    };
 
     let out_name = matches
         .value_of("output")
@@ -81,46 +270,18 @@ fn wrapped_main() -> Result<()> {
                 .to_string()
         });
 
-    if let Some(scroll) = meta.scroll() {
-        save_scroll(&out_name, &dot_arr, scroll, &background)?;
-
-        if let Some(fps_str) = matches.value_of("ffmpeg") {
-            let mp4_name = format!("{}.mp4", out_name);
-            let fps = fps_str.parse::<usize>()?;
-
-            eprintln!("====vv ffmpeg encoding vv====");
-
-            let output = Command::new("ffmpeg")
-                .args(&[
-                    "-y",
-                    "-r",
-                    &fps.to_string(),
-                    "-i",
-                    &format!("{}/%08d.png", out_name),
-                    "-vcodec",
-                    "libx264",
-                    "-pix_fmt",
-                    "yuv420p",
-                    &mp4_name,
-                ])
-                .stdin(Stdio::inherit())
-                .stdout(Stdio::inherit())
-                .stderr(Stdio::inherit())
-                .output()?;
-
-            eprintln!("====^^ ffmpeg encoding ^^====");
-
-            if !output.status.success() {
-                return Err(format_err!(
-                    "ffmpeg encoding failed({})",
-                    output
-                        .status
-                        .code()
-                        .map(|c| c.to_string())
-                        .unwrap_or_else(|| "?".to_string())
-                ));
-            }
-        }
+    let (frames, animated) = render_frames(&source, &meta, &renderer)?;
+    let first = frames
+        .first()
+        .ok_or_else(|| format_err!("no frames to render"))?;
+    let dot_arr = DotArray::new(first.array.clone(), dot);
+
+    if animated {
+        save_animation(&out_name, &frames, &dot_arr, &meta, &matches)?;
+    } else if matches.value_of("format") == Some("svg") {
+        save_svg(format!("{}.svg", out_name), dot_arr.array(), &meta.svg())?;
+    } else if matches.value_of("format").unwrap_or("png") != "png" {
+        save_animation(&out_name, &frames, &dot_arr, &meta, &matches)?;
     } else {
         let dir_name = if !out_name.ends_with(".png") {
             out_name.to_string()
@@ -128,7 +289,210 @@ fn wrapped_main() -> Result<()> {
             out_name + ".png"
         };
 
-        save::save_image(dir_name, &dot_arr)?;
+        if logical {
+            save::save_image(dir_name, dot_arr.array())?;
+        } else {
+            save::save_image(dir_name, &dot_arr)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn play_main(matches: &ArgMatches) -> Result<()> {
+    let protocol_name = matches.value_of("protocol").unwrap();
+    let protocol = match protocol_name {
+        "artnet" => Protocol::ArtNet,
+        "ddp" => Protocol::Ddp,
+        "drgb" => Protocol::Drgb,
+        "dnrgb" => Protocol::Dnrgb,
+        _ => Protocol::E131,
+    };
+    let universe = match (matches.value_of("universe"), protocol.universes()) {
+        (Some(universe), Some(universes)) => {
+            let parsed = universe
+                .parse::<u16>()
+                .map_err(|_| format_err!("invalid universe '{}'", universe))?;
+            if !universes.contains(&parsed) {
+                return Err(format_err!(
+                    "universe must be from {} to {}, but {}",
+                    universes.start(),
+                    universes.end(),
+                    parsed
+                ));
+            }
+            parsed
+        }
+        (Some(_), None) => {
+            return Err(format_err!(
+                "--universe cannot be used with {}",
+                protocol_name
+            ))
+        }
+        (None, _) => protocol.first_universe(),
+    };
+
+    let source = Source::load(matches.value_of("source").unwrap(), &parse_vars(matches)?)?;
+    let meta = match matches.value_of("fps") {
+        Some(fps) => source.metadata().with_fps(parse_fps(fps)?),
+        None => source.metadata(),
+    };
+
+    let font_set = FontSet::new("./fonts", meta.font())?;
+    let renderer = Renderer::new(&meta, font_set)?;
+
+    let (frames, _) = render_frames(&source, &meta, &renderer)?;
+
+    let fps = meta
+        .fps()
+        .ok_or_else(|| format_err!("play requires meta.fps or --fps"))?;
+
+    let options = PlayOptions {
+        protocol,
+        target: matches.value_of("target").unwrap().to_string(),
+        universe,
+        channel_order: matches.value_of("channel-order").unwrap().to_string(),
+        fps,
+        loops: if matches.is_present("loop") {
+            None
+        } else {
+            Some(1)
+        },
+    };
+    play(&frames, meta.wiring(), &options)
+}
+
+/// Parses an fps flag, which takes the place of meta.fps.
+fn parse_fps(s: &str) -> Result<f32> {
+    let fps = s
+        .parse::<f32>()
+        .map_err(|_| format_err!("invalid fps '{}'", s))?;
+    if !fps.is_finite() || fps <= 0.0 {
+        return Err(format_err!("fps must be positive, but {}", s));
+    }
+
+    Ok(fps)
+}
+
+fn parse_vars(matches: &ArgMatches) -> Result<HashMap<String, String>> {
+    let mut vars = HashMap::new();
+    for set in matches.values_of("set").into_iter().flatten() {
+        let mut kv = set.splitn(2, '=');
+        let name = kv.next().unwrap();
+        let value = kv
+            .next()
+            .ok_or_else(|| format_err!("--set expects NAME=VALUE, but '{}'", set))?;
+        vars.insert(name.to_string(), value.to_string());
+    }
+
+    Ok(vars)
+}
+
+/// Logical frames of the source, and whether they are an animation.
+/// A still image is a single frame.
+fn render_frames(
+    source: &Source,
+    meta: &Metadata,
+    renderer: &Renderer,
+) -> Result<(Vec<Frame>, bool)> {
+    if let Some(zones) = source.zones() {
+        return Ok((zone_frames(zones, meta, renderer)?, true));
+    }
+
+    if let Some(scenes) = source.scenes() {
+        return Ok((timeline_frames(scenes, meta, renderer)?, true));
+    }
+
+    let (arr, layout) = renderer.build(source.body())?;
+
+    if let Some(scroll) = meta.scroll() {
+        let frames = scroll_frames(&arr, &layout, scroll, renderer.background(), meta.fps())?;
+        Ok((frames, true))
+    } else if !layout.effects.is_empty() {
+        let fps = meta
+            .fps()
+            .ok_or_else(|| format_err!("text effects require meta.fps"))?;
+        let frames = (0..effect::cycle_frames(&layout.effects, fps)?)
+            .map(|i| Frame {
+                array: effect::apply(&arr, &layout.effects, i as f32 / fps),
+                hold: 1,
+            })
+            .collect();
+        Ok((frames, true))
+    } else {
+        let frame = Frame {
+            array: arr,
+            hold: 1,
+        };
+        Ok((vec![frame], false))
+    }
+}
+
+fn save_animation(
+    out_name: &str,
+    frames: &[Frame],
+    dot_arr: &DotArray,
+    meta: &Metadata,
+    matches: &ArgMatches,
+) -> Result<()> {
+    let fps = meta.fps();
+    let repeat = match matches.value_of("loop-count") {
+        Some(count) => Some(count.parse::<u16>()?),
+        None => None,
+    };
+
+    match matches.value_of("format") {
+        Some("gif") => save_gif(format!("{}.gif", out_name), frames, dot_arr, fps, repeat)?,
+        Some("apng") => save_apng(format!("{}.png", out_name), frames, dot_arr, fps, repeat)?,
+        Some("y4m") => save_y4m(format!("{}.y4m", out_name), frames, dot_arr, fps)?,
+        Some("svg") => save_svg_frames(out_name, frames, &meta.svg())?,
+        Some("bin") => save_export(
+            format!("{}.bin", out_name),
+            frames,
+            fps,
+            &meta.export(),
+            meta.wiring(),
+            Container::Binary,
+        )?,
+        Some("c") => save_export(
+            format!("{}.c", out_name),
+            frames,
+            fps,
+            &meta.export(),
+            meta.wiring(),
+            Container::C,
+        )?,
+        Some("rust") => save_export(
+            format!("{}.rs", out_name),
+            frames,
+            fps,
+            &meta.export(),
+            meta.wiring(),
+            Container::Rust,
+        )?,
+        _ if matches.is_present("no-frames") => {}
+        _ => save_frames(out_name, frames, dot_arr)?,
+    }
+
+    if matches.is_present("ffmpeg") {
+        let container = matches.value_of("container");
+        let output = matches
+            .value_of("video-output")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| format!("{}.{}", out_name, container.unwrap_or("mp4")).into());
+
+        let options = VideoOptions {
+            program: matches.value_of("ffmpeg-bin").unwrap().to_string(),
+            codec: matches.value_of("codec").unwrap().to_string(),
+            pix_fmt: matches.value_of("pix-fmt").unwrap().to_string(),
+            crf: matches.value_of("crf").map(|s| s.to_string()),
+            bitrate: matches.value_of("bitrate").map(|s| s.to_string()),
+            container: container.map(|s| s.to_string()),
+            output,
+        };
+        // meta.fps was set from --ffmpeg before the frames were generated.
+        let fps = meta.fps().unwrap();
+        encode_video(frames, dot_arr, fps, &options)?;
     }
 
     Ok(())
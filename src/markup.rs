@@ -0,0 +1,127 @@
+#[cfg(test)]
+mod test;
+
+use failure::format_err;
+
+use crate::result::Result;
+
+/// Attributes set by a markup tag.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Attrs {
+    pub foreground: Option<String>,
+    pub background: Option<String>,
+    pub style: Option<String>,
+    pub font: Option<String>,
+}
+
+/// A part of content with the attributes of the tags enclosing it, from the outermost.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Segment {
+    Text(String, Vec<Attrs>),
+    Space(usize, Vec<Attrs>),
+}
+
+/// Splits `content` into segments.
+///
+/// `{red}` is a shorthand of `{f=red}`, and `{f=red,b=black,s=style,font=name}` sets attributes
+/// until the matching `{/}`. `{space=N}` inserts a space of `N` columns.
+/// `{{` and `}}` are a literal brace.
+pub fn parse(content: &str) -> Result<Vec<Segment>> {
+    let mut segments = Vec::new();
+    let mut stack: Vec<(usize, Attrs)> = Vec::new();
+    let mut text = String::new();
+
+    let mut chars = content.chars().enumerate().peekable();
+    while let Some((pos, c)) = chars.next() {
+        match c {
+            '{' if chars.peek().map(|&(_, c)| c) == Some('{') => {
+                chars.next();
+                text.push('{');
+            }
+            '}' if chars.peek().map(|&(_, c)| c) == Some('}') => {
+                chars.next();
+                text.push('}');
+            }
+            '}' => return Err(format_err!("unescaped '}}' at position {}", pos)),
+            '{' => {
+                let mut tag = String::new();
+                loop {
+                    match chars.next() {
+                        Some((_, '}')) => break,
+                        Some((_, c)) => tag.push(c),
+                        None => return Err(format_err!("unterminated tag at position {}", pos)),
+                    }
+                }
+
+                let current = stack
+                    .iter()
+                    .map(|(_, attrs)| attrs.clone())
+                    .collect::<Vec<_>>();
+                if !text.is_empty() {
+                    segments.push(Segment::Text(text.split_off(0), current.clone()));
+                }
+
+                let tag = tag.trim();
+                if tag == "/" {
+                    if stack.is_empty() {
+                        return Err(format_err!("unmatched '{{/}}' at position {}", pos));
+                    }
+                    stack.pop();
+                } else if let Some(width) = tag.strip_prefix("space=") {
+                    let width = width.trim().parse::<usize>().map_err(|e| {
+                        format_err!("invalid space width at position {}: {}", pos, e)
+                    })?;
+                    segments.push(Segment::Space(width, current));
+                } else {
+                    let attrs =
+                        parse_tag(tag).map_err(|e| format_err!("{} at position {}", e, pos))?;
+                    stack.push((pos, attrs));
+                }
+            }
+            c => text.push(c),
+        }
+    }
+
+    if let Some((pos, _)) = stack.last() {
+        return Err(format_err!("unclosed tag at position {}", pos));
+    }
+
+    if !text.is_empty() {
+        segments.push(Segment::Text(text, Vec::new()));
+    }
+
+    Ok(segments)
+}
+
+fn parse_tag(tag: &str) -> Result<Attrs> {
+    if tag.is_empty() {
+        return Err(format_err!("empty tag"));
+    }
+
+    if !tag.contains('=') {
+        return Ok(Attrs {
+            foreground: Some(tag.to_string()),
+            ..Attrs::default()
+        });
+    }
+
+    let mut attrs = Attrs::default();
+    for item in tag.split(',') {
+        let mut kv = item.splitn(2, '=');
+        let key = kv.next().unwrap().trim();
+        let value = kv
+            .next()
+            .map(|v| v.trim().to_string())
+            .ok_or_else(|| format_err!("missing value for '{}'", key))?;
+
+        match key {
+            "f" => attrs.foreground = Some(value),
+            "b" => attrs.background = Some(value),
+            "s" => attrs.style = Some(value),
+            "font" => attrs.font = Some(value),
+            _ => return Err(format_err!("unknown attribute '{}'", key)),
+        }
+    }
+
+    Ok(attrs)
+}
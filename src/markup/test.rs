@@ -0,0 +1,65 @@
+use super::*;
+
+fn fore(name: &str) -> Attrs {
+    Attrs {
+        foreground: Some(name.to_string()),
+        ..Attrs::default()
+    }
+}
+
+fn back(name: &str) -> Attrs {
+    Attrs {
+        background: Some(name.to_string()),
+        ..Attrs::default()
+    }
+}
+
+#[test]
+fn test_parse_plain() {
+    assert_eq!(
+        parse("Next: Shinjuku").unwrap(),
+        vec![Segment::Text("Next: Shinjuku".to_string(), vec![])]
+    );
+}
+
+#[test]
+fn test_parse_tags() {
+    assert_eq!(
+        parse("Next: {red}Shin{b=black}juku{/}{/} {space=2}(rapid)").unwrap(),
+        vec![
+            Segment::Text("Next: ".to_string(), vec![]),
+            Segment::Text("Shin".to_string(), vec![fore("red")]),
+            Segment::Text("juku".to_string(), vec![fore("red"), back("black")]),
+            Segment::Text(" ".to_string(), vec![]),
+            Segment::Space(2, vec![]),
+            Segment::Text("(rapid)".to_string(), vec![]),
+        ]
+    );
+}
+
+#[test]
+fn test_parse_escape() {
+    assert_eq!(
+        parse("{{{red}x}}{/}").unwrap(),
+        vec![
+            Segment::Text("{".to_string(), vec![]),
+            Segment::Text("x}".to_string(), vec![fore("red")]),
+        ]
+    );
+}
+
+#[test]
+fn test_parse_errors() {
+    let samples = vec![
+        ("ab{red}cd", "unclosed tag at position 2"),
+        ("ab{/}", "unmatched '{/}' at position 2"),
+        ("ab{red", "unterminated tag at position 2"),
+        ("a}b", "unescaped '}' at position 1"),
+        ("{x=1}a{/}", "unknown attribute 'x' at position 0"),
+    ];
+
+    for (content, message) in samples {
+        let err = parse(content).unwrap_err();
+        assert_eq!(err.to_string(), message, "parsing '{}'", content);
+    }
+}
@@ -2,6 +2,7 @@ pub mod array;
 pub mod color;
 pub mod command;
 pub mod dot_array;
+pub mod style;
 
 use color::Color;
 
@@ -9,3 +10,9 @@ pub trait Imageable {
     fn dim(&self) -> (usize, usize);
     fn get(&self, x: usize, y: usize) -> Color;
 }
+
+/// Settings of optional fields, which a text and the styles around it set in layers.
+pub trait Fallback {
+    /// Each field of `self`, or of `base` if not set.
+    fn or(self, base: &Self) -> Self;
+}
@@ -1,3 +1,5 @@
+use std::ops::Range;
+
 use super::color::Color;
 
 #[derive(Debug, Clone)]
@@ -21,6 +23,15 @@ impl Array {
         }
     }
 
+    /// An array of `width` x `height` cells of `color`.
+    #[cfg(test)]
+    pub fn filled(width: usize, height: usize, color: &Color) -> Array {
+        Array {
+            cells: vec![vec![color.clone(); height]; width],
+            height,
+        }
+    }
+
     pub fn add_line(&mut self, line: Vec<Color>) {
         if line.len() != self.height {
             panic!("mismatch height");
@@ -37,6 +48,10 @@ impl Array {
         }
     }
 
+    pub fn width(&self) -> usize {
+        self.cells.len()
+    }
+
     pub fn height(&self) -> usize {
         self.height
     }
@@ -55,6 +70,152 @@ impl Array {
         new_array
     }
 
+    /// Like `map`, but `f` also takes the column and the row of the cell.
+    pub fn map_indexed(&self, f: impl Fn(usize, usize, &Color) -> Color) -> Array {
+        let mut new_array = Array::with_dim(self.cells.len(), self.height());
+
+        for (x, line) in self.cells.iter().enumerate() {
+            let new_line = line.iter().enumerate().map(|(y, p)| f(x, y, p)).collect();
+            new_array.add_line(new_line);
+        }
+
+        new_array
+    }
+
+    /// Replaces each cell in `columns` with `f` of it. Columns past the width are left out.
+    pub fn update_columns(&mut self, columns: Range<usize>, f: impl Fn(&Color) -> Color) {
+        let end = columns.end.min(self.cells.len());
+
+        for line in &mut self.cells[columns.start.min(end)..end] {
+            for p in line.iter_mut() {
+                *p = f(p);
+            }
+        }
+    }
+
+    pub fn slice(&self, offset: isize, width: usize, background: &Color) -> Array {
+        let height = self.height;
+
+        let blank_line: Vec<Color> = (0..height).map(|_| background.clone()).collect();
+
+        let mut new_array = Array::with_dim(width, height);
+
+        for i in 0..width {
+            let real_x = (i as isize) + offset;
+
+            if real_x < 0 {
+                new_array.add_line(blank_line.clone());
+            } else {
+                new_array.add_line(
+                    self.get_line(real_x as usize)
+                        .cloned()
+                        .unwrap_or_else(|| blank_line.clone()),
+                );
+            }
+        }
+
+        new_array
+    }
+
+    pub fn slice_vertical(&self, offset: isize, height: usize, background: &Color) -> Array {
+        let mut new_array = Array::with_dim(self.cells.len(), height);
+
+        for line in &self.cells {
+            let new_line = (0..height)
+                .map(|i| {
+                    let real_y = (i as isize) + offset;
+
+                    if real_y < 0 || real_y as usize >= self.height {
+                        background.clone()
+                    } else {
+                        line[real_y as usize].clone()
+                    }
+                })
+                .collect();
+            new_array.add_line(new_line);
+        }
+
+        new_array
+    }
+
+    /// Draws `other` with its top left corner at (`x`, `y`), clipped to this array.
+    pub fn paste(&mut self, other: &Array, x: usize, y: usize) {
+        for (line, other_line) in self.cells.iter_mut().skip(x).zip(&other.cells) {
+            for (cell, other_cell) in line.iter_mut().skip(y).zip(other_line) {
+                *cell = other_cell.clone();
+            }
+        }
+    }
+
+    pub fn tile(&self, count: usize) -> Array {
+        let mut new_array = Array::with_dim(self.cells.len() * count, self.height);
+
+        for _ in 0..count {
+            for line in &self.cells {
+                new_array.add_line(line.clone());
+            }
+        }
+
+        new_array
+    }
+
+    pub fn tile_vertical(&self, count: usize) -> Array {
+        let mut new_array = Array::with_dim(self.cells.len(), self.height * count);
+
+        for line in &self.cells {
+            let mut new_line = Vec::with_capacity(self.height * count);
+            for _ in 0..count {
+                new_line.extend(line.iter().cloned());
+            }
+            new_array.add_line(new_line);
+        }
+
+        new_array
+    }
+
+    /// Slices at a fractional offset, blending the two nearest columns.
+    pub fn slice_blend(&self, offset: f32, width: usize, background: &Color) -> Array {
+        let base = offset.floor();
+        let t = offset - base;
+
+        let a = self.slice(base as isize, width, background);
+        if t == 0.0 {
+            return a;
+        }
+        let b = self.slice(base as isize + 1, width, background);
+
+        a.blend(&b, t)
+    }
+
+    /// Slices at a fractional offset, blending the two nearest rows.
+    pub fn slice_vertical_blend(&self, offset: f32, height: usize, background: &Color) -> Array {
+        let base = offset.floor();
+        let t = offset - base;
+
+        let a = self.slice_vertical(base as isize, height, background);
+        if t == 0.0 {
+            return a;
+        }
+        let b = self.slice_vertical(base as isize + 1, height, background);
+
+        a.blend(&b, t)
+    }
+
+    pub fn blend(&self, other: &Array, t: f32) -> Array {
+        let mut new_array = Array::with_dim(self.cells.len(), self.height());
+
+        for (line, other_line) in self.cells.iter().zip(other.cells.iter()) {
+            new_array.add_line(
+                line.iter()
+                    .zip(other_line.iter())
+                    .map(|(p, q)| p.mix(q, t))
+                    .collect(),
+            );
+        }
+
+        new_array
+    }
+
     pub fn iter(&self) -> impl Iterator<Item = (usize, usize, &Color)> {
         self.cells
             .iter()
@@ -55,6 +55,16 @@ impl Color {
         .normalize()
     }
 
+    pub fn mix(&self, other: &Color, t: f32) -> Color {
+        Color {
+            r: self.r + (other.r - self.r) * t,
+            g: self.g + (other.g - self.g) * t,
+            b: self.b + (other.b - self.b) * t,
+            a: self.a + (other.a - self.a) * t,
+        }
+        .normalize()
+    }
+
     fn normalize(self) -> Color {
         Color {
             r: self.r.max(0.0).min(1.0),
@@ -79,6 +89,12 @@ impl Color {
     pub fn a<V: ColorValue>(&self) -> V {
         V::from_f32(self.a)
     }
+
+    /// Color channels with the alpha drawn over black.
+    pub fn rgb(&self) -> [u8; 3] {
+        let channel = |v: f32| (v * self.a * 255.0).round() as u8;
+        [channel(self.r), channel(self.g), channel(self.b)]
+    }
 }
 
 impl PartialEq for Color {
@@ -1,15 +1,149 @@
-pub trait Command<'a, T, S> {
+use failure::format_err;
+use serde_derive::Deserialize;
+
+use super::Fallback;
+use crate::result::Result;
+
+pub trait Command<'a, T, S, P> {
     fn when_text<U>(&'a self, none: U, f: impl FnOnce(&T) -> U) -> U;
     fn when_space<U>(&'a self, none: U, f: impl FnOnce(&S) -> U) -> U;
+    fn when_pause<U>(&'a self, none: U, f: impl FnOnce(&P) -> U) -> U;
 }
 
 pub trait Text {
     fn content(&self) -> &str;
     fn foreground(&self) -> Option<&str>;
     fn background(&self) -> Option<&str>;
+    fn fit(&self) -> FitFields;
+    fn style(&self) -> Option<&str>;
+    fn font(&self) -> Option<&str>;
+    fn spacing(&self) -> Option<usize>;
+    fn effect(&self) -> EffectFields;
 }
 
 pub trait Space {
     fn width(&self) -> usize;
     fn background(&self) -> Option<&str>;
+    fn style(&self) -> Option<&str>;
+}
+
+pub trait Pause {
+    fn frames(&self) -> Option<usize>;
+    fn seconds(&self) -> Option<f32>;
+    fn align(&self) -> Align;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Deserialize)]
+pub enum Align {
+    #[serde(rename = "left")]
+    Left,
+    #[default]
+    #[serde(rename = "center")]
+    Center,
+    #[serde(rename = "right")]
+    Right,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub enum FitMode {
+    #[serde(rename = "condense")]
+    Condense,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Fit {
+    pub width: Option<usize>,
+    pub min_ratio: f32,
+}
+
+/// Fit fields as written on a text or a style, so that a text overrides a style field by field.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct FitFields {
+    pub mode: Option<FitMode>,
+    pub width: Option<usize>,
+    pub min_ratio: Option<f32>,
+}
+
+impl Fallback for FitFields {
+    fn or(self, base: &FitFields) -> FitFields {
+        FitFields {
+            mode: self.mode.or(base.mode),
+            width: self.width.or(base.width),
+            min_ratio: self.min_ratio.or(base.min_ratio),
+        }
+    }
+}
+
+impl FitFields {
+    /// The fit to apply, if there is a mode.
+    pub fn resolve(self) -> Option<Fit> {
+        self.mode.map(|FitMode::Condense| Fit {
+            width: self.width,
+            min_ratio: self.min_ratio.unwrap_or(0.5),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub enum EffectKind {
+    #[serde(rename = "blink")]
+    Blink,
+    #[serde(rename = "invert-blink")]
+    InvertBlink,
+    #[serde(rename = "flash")]
+    Flash,
+}
+
+/// A time-varying color effect on a text.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Effect {
+    pub kind: EffectKind,
+    /// Seconds of one cycle.
+    pub period: f32,
+    /// Fraction of the cycle in which the text is shown as is.
+    pub duty: f32,
+}
+
+/// Effect fields as written on a text or a style, so that a text overrides a style field by field.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct EffectFields {
+    pub kind: Option<EffectKind>,
+    pub period: Option<f32>,
+    pub duty: Option<f32>,
+}
+
+impl Fallback for EffectFields {
+    fn or(self, base: &EffectFields) -> EffectFields {
+        EffectFields {
+            kind: self.kind.or(base.kind),
+            period: self.period.or(base.period),
+            duty: self.duty.or(base.duty),
+        }
+    }
+}
+
+impl EffectFields {
+    /// The effect to apply, if there is a kind.
+    /// Fails unless the period is positive and the duty between 0 and 1.
+    pub fn resolve(self) -> Result<Option<Effect>> {
+        let kind = match self.kind {
+            Some(kind) => kind,
+            None => return Ok(None),
+        };
+
+        let period = self.period.unwrap_or(1.0);
+        if !period.is_finite() || period <= 0.0 {
+            return Err(format_err!(
+                "effect-period must be positive, got {}",
+                period
+            ));
+        }
+
+        let duty = self.duty.unwrap_or(0.5);
+        if !(0.0..=1.0).contains(&duty) {
+            return Err(format_err!("duty must be between 0 and 1, got {}", duty));
+        }
+
+        Ok(Some(Effect { kind, period, duty }))
+    }
 }
@@ -8,7 +8,7 @@ use super::Imageable;
 pub struct DotArray {
     array: Array,
     dot_table: HashMap<Color, Array>,
-    dot_dim: (usize, usize),
+    dot: Array,
 }
 
 impl DotArray {
@@ -29,53 +29,50 @@ impl DotArray {
         DotArray {
             array,
             dot_table,
-            dot_dim: dot.dim(),
+            dot,
         }
     }
 
-    pub fn dim_in_dot(&self) -> (usize, usize) {
-        self.array.dim()
-    }
-
-    pub fn slice<'a>(&'a self, offset: isize, width: usize, background: &'a Color) -> DotArray {
-        let height = self.array.height();
-
-        let blank_line: Vec<Color> = (0..height).map(|_| background.clone()).collect();
+    /// Makes a `DotArray` of `array` with the same dot, reusing the dot table.
+    pub fn with_array(&self, array: Array) -> DotArray {
+        let mut dot_table = self.dot_table.clone();
 
-        let mut new_array = Array::with_dim(width, height);
-
-        for i in 0..width {
-            let real_x = (i as isize) + offset;
-
-            if real_x < 0 {
-                new_array.add_line(blank_line.clone());
-            } else {
-                new_array.add_line(
-                    self.array
-                        .get_line(real_x as usize)
-                        .cloned()
-                        .unwrap_or_else(|| blank_line.clone()),
-                );
+        for (_, _, c) in array.iter() {
+            if !dot_table.contains_key(c) {
+                let c_dot = self.dot.map(|p| p.cross(c));
+                dot_table.insert(c.clone(), c_dot);
             }
         }
 
         DotArray {
-            array: new_array,
-            dot_table: self.dot_table.clone(),
-            dot_dim: self.dot_dim,
+            array,
+            dot_table,
+            dot: self.dot.clone(),
         }
     }
+
+    pub fn array(&self) -> &Array {
+        &self.array
+    }
+
+    /// All colors that can appear in an image, i.e. the pixels of every dot in the table.
+    pub fn dot_colors(&self) -> HashSet<Color> {
+        self.dot_table
+            .values()
+            .flat_map(|dot| dot.iter().map(|(_, _, c)| c.clone()))
+            .collect()
+    }
 }
 
 impl Imageable for DotArray {
     fn dim(&self) -> (usize, usize) {
         let (ax, ay) = self.array.dim();
-        let (dx, dy) = self.dot_dim;
+        let (dx, dy) = self.dot.dim();
         (ax * dx, ay * dy)
     }
 
     fn get(&self, x: usize, y: usize) -> Color {
-        let (dx, dy) = self.dot_dim;
+        let (dx, dy) = self.dot.dim();
 
         let ap = self.array.get(x / dx, y / dy);
 
@@ -0,0 +1,65 @@
+#[cfg(test)]
+mod test;
+
+use std::collections::HashMap;
+
+use failure::format_err;
+
+use super::command::{EffectFields, FitFields, Text};
+use super::Fallback;
+use crate::result::Result;
+
+#[derive(Debug, Clone, Default)]
+pub struct Style {
+    pub foreground: Option<String>,
+    pub background: Option<String>,
+    pub font: Option<String>,
+    pub spacing: Option<usize>,
+    pub fit: FitFields,
+    pub effect: EffectFields,
+}
+
+impl Style {
+    /// The style of `text`: its own fields, falling back to this style field by field.
+    pub fn under(&self, text: &dyn Text) -> Style {
+        Style {
+            foreground: text.foreground().map(|s| s.to_string()),
+            background: text.background().map(|s| s.to_string()),
+            font: text.font().map(|s| s.to_string()),
+            spacing: text.spacing(),
+            fit: text.fit(),
+            effect: text.effect(),
+        }
+        .or(self)
+    }
+}
+
+impl Fallback for Style {
+    fn or(self, base: &Style) -> Style {
+        Style {
+            foreground: self.foreground.or_else(|| base.foreground.clone()),
+            background: self.background.or_else(|| base.background.clone()),
+            font: self.font.or_else(|| base.font.clone()),
+            spacing: self.spacing.or(base.spacing),
+            fit: self.fit.or(&base.fit),
+            effect: self.effect.or(&base.effect),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct StyleSet {
+    set: HashMap<String, Style>,
+}
+
+impl StyleSet {
+    pub fn new(set: HashMap<String, Style>) -> StyleSet {
+        StyleSet { set }
+    }
+
+    pub fn get(&self, name: &str) -> Result<&Style> {
+        self.set
+            .get(name)
+            .ok_or_else(|| format_err!("unknown style: '{}'", name))
+    }
+}
@@ -0,0 +1,104 @@
+use super::*;
+use crate::model::command::{Effect, EffectKind, Fit};
+use crate::source::{Metadata, Text as TextCommand};
+
+fn style_set() -> StyleSet {
+    let meta: Metadata = serde_yaml::from_str(
+        "
+font: base
+colors: {red: '#ff0000', black: '#000000'}
+styles:
+  warning:
+    f: red
+    b: black
+    spacing: 2
+    fit: condense
+    min-ratio: 0.7
+    effect: blink
+    effect-period: 2.0
+",
+    )
+    .unwrap();
+    meta.style_set()
+}
+
+fn text(yaml: &str) -> TextCommand {
+    serde_yaml::from_str(yaml).unwrap()
+}
+
+#[test]
+fn test_style_fields() {
+    let style = style_set()
+        .get("warning")
+        .unwrap()
+        .under(&text("{c: A, s: warning}"));
+
+    assert_eq!(style.foreground.as_ref().unwrap(), "red");
+    assert_eq!(style.background.as_ref().unwrap(), "black");
+    assert_eq!(style.font, None);
+    assert_eq!(style.spacing, Some(2));
+    assert_eq!(
+        style.fit.resolve(),
+        Some(Fit {
+            width: None,
+            min_ratio: 0.7
+        })
+    );
+    assert_eq!(
+        style.effect.resolve().unwrap(),
+        Some(Effect {
+            kind: EffectKind::Blink,
+            period: 2.0,
+            duty: 0.5
+        })
+    );
+}
+
+#[test]
+fn test_text_overrides_field_by_field() {
+    let style = style_set().get("warning").unwrap().under(&text(
+        "{c: A, s: warning, f: yellow, spacing: 0, fit-width: 20, duty: 0.25}",
+    ));
+
+    assert_eq!(style.foreground.as_ref().unwrap(), "yellow");
+    assert_eq!(style.background.as_ref().unwrap(), "black");
+    assert_eq!(style.spacing, Some(0));
+    // Fit and effect fields of the text are merged with those of the style.
+    assert_eq!(
+        style.fit.resolve(),
+        Some(Fit {
+            width: Some(20),
+            min_ratio: 0.7
+        })
+    );
+    assert_eq!(
+        style.effect.resolve().unwrap(),
+        Some(Effect {
+            kind: EffectKind::Blink,
+            period: 2.0,
+            duty: 0.25
+        })
+    );
+}
+
+#[test]
+fn test_unstyled_text() {
+    let style = Style::default().under(&text("{c: A, fit-width: 20, effect-period: 3.0}"));
+
+    assert_eq!(style.foreground, None);
+    assert_eq!(style.fit.resolve(), None);
+    assert_eq!(style.effect.resolve().unwrap(), None);
+}
+
+#[test]
+fn test_invalid_effect() {
+    let resolve = |yaml| Style::default().under(&text(yaml)).effect.resolve();
+
+    let err = resolve("{c: A, effect: blink, effect-period: 0}").unwrap_err();
+    assert_eq!(err.to_string(), "effect-period must be positive, got 0");
+
+    let err = resolve("{c: A, effect: flash, duty: 1.5}").unwrap_err();
+    assert_eq!(err.to_string(), "duty must be between 0 and 1, got 1.5");
+
+    assert!(resolve("{c: A, effect: blink, duty: 1}").is_ok());
+}
@@ -0,0 +1,353 @@
+#[cfg(test)]
+mod test;
+
+use std::net::{SocketAddr, ToSocketAddrs, UdpSocket};
+use std::ops::RangeInclusive;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use failure::format_err;
+
+use crate::export::scan;
+use crate::frame::Frame;
+use crate::model::array::Array;
+use crate::model::Imageable;
+use crate::result::Result;
+use crate::source::{Scan, Wiring};
+use crate::wiring::led_order;
+
+/// Channels in a universe. A whole number of LEDs fits in it.
+const UNIVERSE_CHANNELS: usize = 510;
+/// Data bytes in a DDP packet, a whole number of LEDs.
+const DDP_DATA: usize = 1440;
+/// LEDs in a DRGB packet.
+const DRGB_LEDS: usize = 490;
+/// LEDs in a DNRGB packet.
+const DNRGB_LEDS: usize = 489;
+/// Seconds WLED waits for the next packet before going back to its own effects.
+const WLED_TIMEOUT: u8 = 2;
+
+/// How frames are sent to the controller.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Protocol {
+    /// E1.31 (sACN) data packets.
+    E131,
+    /// Art-Net ArtDmx packets.
+    ArtNet,
+    /// Distributed Display Protocol.
+    Ddp,
+    /// WLED realtime UDP with all LEDs in one packet.
+    Drgb,
+    /// WLED realtime UDP with a start index in each packet.
+    Dnrgb,
+}
+
+impl Protocol {
+    /// Universe numbering starts at 1 in E1.31 and at 0 in Art-Net.
+    /// The other protocols have no universes.
+    pub fn first_universe(self) -> u16 {
+        match self {
+            Protocol::E131 => 1,
+            _ => 0,
+        }
+    }
+
+    /// Universes the protocol can address.
+    pub fn universes(self) -> Option<RangeInclusive<u16>> {
+        match self {
+            Protocol::E131 => Some(1..=63999),
+            Protocol::ArtNet => Some(0..=0x7fff),
+            _ => None,
+        }
+    }
+
+    fn port(self) -> u16 {
+        match self {
+            Protocol::E131 => 5568,
+            Protocol::ArtNet => 6454,
+            Protocol::Ddp => 4048,
+            Protocol::Drgb | Protocol::Dnrgb => 21324,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PlayOptions {
+    pub protocol: Protocol,
+    /// `HOST` or `HOST:PORT`. The port defaults to the one of the protocol.
+    pub target: String,
+    /// Universe of the first LED. Following LEDs go on to the next universes.
+    pub universe: u16,
+    /// Order of color channels, e.g. `grb`.
+    pub channel_order: String,
+    pub fps: f32,
+    /// Times to play the frames, or again and again until interrupted if not set.
+    pub loops: Option<usize>,
+}
+
+/// Sends frames in the LED order of `wiring`, or row by row if not given.
+/// Each frame is sent again for every frame period it is held.
+pub fn play(frames: &[Frame], wiring: Option<&Wiring>, options: &PlayOptions) -> Result<()> {
+    if !options.fps.is_finite() || options.fps <= 0.0 {
+        return Err(format_err!("fps must be positive, but {}", options.fps));
+    }
+
+    let first = frames
+        .first()
+        .ok_or_else(|| format_err!("no frames to play"))?;
+    let (width, height) = first.array.dim();
+    let leds = match wiring {
+        Some(wiring) => led_order(wiring, width, height)?,
+        None => scan(width, height, Scan::Rows),
+    };
+    let order = channel_order(&options.channel_order)?;
+
+    let target = resolve(&options.target, options.protocol.port())?;
+    let socket = if target.is_ipv4() {
+        let socket = UdpSocket::bind("0.0.0.0:0")?;
+        // Art-Net nodes are often reached by broadcast.
+        socket.set_broadcast(true)?;
+        socket
+    } else {
+        UdpSocket::bind("[::]:0")?
+    };
+
+    let cid = cid();
+    let period = Duration::from_secs_f32(1.0 / options.fps);
+    let start = Instant::now();
+    let mut tick = 0;
+    let mut sequence = 0;
+    let mut played = 0;
+
+    let mut data = Vec::with_capacity(frames.len());
+    for frame in frames {
+        if frame.array.dim() != (width, height) {
+            return Err(format_err!("frame size differs from the first frame"));
+        }
+        data.push(channels(&frame.array, &leds, order));
+    }
+
+    loop {
+        for (frame, data) in frames.iter().zip(&data) {
+            for _ in 0..frame.hold {
+                // Paced on the start time to avoid drift.
+                let deadline = start + period * tick;
+                let now = Instant::now();
+                if deadline > now {
+                    thread::sleep(deadline - now);
+                }
+                // 0 means no sequence in Art-Net.
+                sequence = sequence % 255 + 1;
+
+                for packet in packets(options, &cid, sequence, data)? {
+                    socket.send_to(&packet, target)?;
+                }
+                tick += 1;
+            }
+        }
+
+        played += 1;
+        if matches!(options.loops, Some(loops) if played >= loops) {
+            return Ok(());
+        }
+    }
+}
+
+/// Packets carrying the channel `data` of a frame.
+fn packets(
+    options: &PlayOptions,
+    cid: &[u8; 16],
+    sequence: u8,
+    data: &[u8],
+) -> Result<Vec<Vec<u8>>> {
+    let universe = |i: usize| {
+        let universe = usize::from(options.universe) + i;
+        match options.protocol.universes() {
+            Some(universes) if universe <= usize::from(*universes.end()) => Ok(universe as u16),
+            _ => Err(format_err!("universe {} is out of range", universe)),
+        }
+    };
+
+    match options.protocol {
+        Protocol::E131 => data
+            .chunks(UNIVERSE_CHANNELS)
+            .enumerate()
+            .map(|(i, slots)| Ok(e131_packet(cid, universe(i)?, sequence, slots)))
+            .collect(),
+        Protocol::ArtNet => data
+            .chunks(UNIVERSE_CHANNELS)
+            .enumerate()
+            .map(|(i, slots)| Ok(artnet_packet(universe(i)?, sequence, slots)))
+            .collect(),
+        Protocol::Ddp => {
+            let count = data.chunks(DDP_DATA).len();
+            Ok(data
+                .chunks(DDP_DATA)
+                .enumerate()
+                .map(|(i, chunk)| {
+                    // DDP sequence numbers run from 1 to 15.
+                    let sequence = (sequence - 1) % 15 + 1;
+                    ddp_packet(sequence, i * DDP_DATA, i + 1 == count, chunk)
+                })
+                .collect())
+        }
+        Protocol::Drgb => {
+            if data.len() > DRGB_LEDS * 3 {
+                return Err(format_err!(
+                    "drgb supports up to {} LEDs, use dnrgb for more",
+                    DRGB_LEDS
+                ));
+            }
+            Ok(vec![wled_packet(2, None, data)])
+        }
+        Protocol::Dnrgb => Ok(data
+            .chunks(DNRGB_LEDS * 3)
+            .enumerate()
+            .map(|(i, chunk)| wled_packet(4, Some((i * DNRGB_LEDS) as u16), chunk))
+            .collect()),
+    }
+}
+
+fn resolve(target: &str, port: u16) -> Result<SocketAddr> {
+    let addrs = match target.parse::<SocketAddr>() {
+        Ok(addr) => vec![addr],
+        Err(_) if target.contains(':') => target.to_socket_addrs()?.collect(),
+        Err(_) => (target, port).to_socket_addrs()?.collect(),
+    };
+
+    addrs
+        .into_iter()
+        .next()
+        .ok_or_else(|| format_err!("cannot resolve '{}'", target))
+}
+
+/// Indices of red, green and blue in the order of `name`.
+fn channel_order(name: &str) -> Result<[usize; 3]> {
+    let mut order = [0; 3];
+
+    if name.len() != 3 {
+        return Err(format_err!("invalid channel order '{}'", name));
+    }
+    for (i, c) in name.chars().enumerate() {
+        order[i] = match c {
+            'r' => 0,
+            'g' => 1,
+            'b' => 2,
+            _ => return Err(format_err!("invalid channel order '{}'", name)),
+        };
+    }
+    if order[0] == order[1] || order[1] == order[2] || order[0] == order[2] {
+        return Err(format_err!("invalid channel order '{}'", name));
+    }
+
+    Ok(order)
+}
+
+fn channels(array: &Array, leds: &[(usize, usize)], order: [usize; 3]) -> Vec<u8> {
+    leds.iter()
+        .flat_map(|&(x, y)| {
+            let color = array.get(x, y).rgb();
+            order.iter().map(move |&i| color[i])
+        })
+        .collect()
+}
+
+/// Component identifier of this sender, unique enough among the senders on a network.
+fn cid() -> [u8; 16] {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+    let mut cid = [0; 16];
+    cid[..12].copy_from_slice(&nanos.to_be_bytes()[4..]);
+    cid[12..].copy_from_slice(&std::process::id().to_be_bytes());
+    cid
+}
+
+/// An E1.31 data packet with the root, framing and DMP layers.
+fn e131_packet(cid: &[u8; 16], universe: u16, sequence: u8, slots: &[u8]) -> Vec<u8> {
+    let len = 126 + slots.len();
+    let flags_length = |start: usize| (0x7000 | (len - start) as u16).to_be_bytes();
+
+    let mut packet = Vec::with_capacity(len);
+
+    // Root layer
+    packet.extend(&0x0010u16.to_be_bytes());
+    packet.extend(&0u16.to_be_bytes());
+    packet.extend(b"ASC-E1.17\0\0\0");
+    packet.extend(&flags_length(16));
+    packet.extend(&4u32.to_be_bytes());
+    packet.extend(cid);
+
+    // Framing layer
+    packet.extend(&flags_length(38));
+    packet.extend(&2u32.to_be_bytes());
+    let mut source_name = [0; 64];
+    source_name[..9].copy_from_slice(b"led-image");
+    packet.extend(&source_name[..]);
+    packet.push(100);
+    packet.extend(&0u16.to_be_bytes());
+    packet.push(sequence);
+    packet.push(0);
+    packet.extend(&universe.to_be_bytes());
+
+    // DMP layer
+    packet.extend(&flags_length(115));
+    packet.push(0x02);
+    packet.push(0xa1);
+    packet.extend(&0u16.to_be_bytes());
+    packet.extend(&1u16.to_be_bytes());
+    packet.extend(&(slots.len() as u16 + 1).to_be_bytes());
+    packet.push(0);
+    packet.extend(slots);
+
+    packet
+}
+
+/// An ArtDmx packet. The data is padded to an even length.
+fn artnet_packet(universe: u16, sequence: u8, slots: &[u8]) -> Vec<u8> {
+    let len = slots.len() + slots.len() % 2;
+    let mut packet = Vec::with_capacity(18 + len);
+
+    packet.extend(b"Art-Net\0");
+    packet.extend(&0x5000u16.to_le_bytes());
+    packet.extend(&14u16.to_be_bytes());
+    packet.push(sequence);
+    packet.push(0);
+    packet.extend(&universe.to_le_bytes());
+    packet.extend(&(len as u16).to_be_bytes());
+    packet.extend(slots);
+    packet.resize(18 + len, 0);
+
+    packet
+}
+
+/// A DDP packet of RGB data at byte `offset`. The last packet of a frame pushes it to the LEDs.
+fn ddp_packet(sequence: u8, offset: usize, push: bool, data: &[u8]) -> Vec<u8> {
+    let mut packet = Vec::with_capacity(10 + data.len());
+
+    // Version 1
+    packet.push(0x40 | if push { 0x01 } else { 0 });
+    packet.push(sequence);
+    // RGB, 8 bits per channel
+    packet.push(0x0b);
+    // Default output device
+    packet.push(1);
+    packet.extend(&(offset as u32).to_be_bytes());
+    packet.extend(&(data.len() as u16).to_be_bytes());
+    packet.extend(data);
+
+    packet
+}
+
+/// A WLED realtime packet of `protocol`, with the index of its first LED for DNRGB.
+fn wled_packet(protocol: u8, start: Option<u16>, data: &[u8]) -> Vec<u8> {
+    let mut packet = vec![protocol, WLED_TIMEOUT];
+
+    if let Some(start) = start {
+        packet.extend(&start.to_be_bytes());
+    }
+    packet.extend(data);
+
+    packet
+}
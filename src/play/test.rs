@@ -0,0 +1,240 @@
+use std::thread;
+use std::time::Duration;
+
+use super::*;
+use crate::export::encode;
+use crate::model::color::Color;
+use crate::source::Export;
+
+fn frame(colors: &[(u8, u8, u8)], hold: usize) -> Frame {
+    let mut array = Array::new(1);
+    for &(r, g, b) in colors {
+        array.add_line(vec![Color::new(r, g, b, 255u8)]);
+    }
+    Frame { array, hold }
+}
+
+fn listen() -> (UdpSocket, String) {
+    let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+    socket
+        .set_read_timeout(Some(Duration::from_secs(5)))
+        .unwrap();
+    let target = socket.local_addr().unwrap().to_string();
+    (socket, target)
+}
+
+fn receive(socket: &UdpSocket) -> Vec<u8> {
+    let mut buf = [0; 2048];
+    let len = socket.recv(&mut buf).unwrap();
+    buf[..len].to_vec()
+}
+
+#[test]
+fn test_e131() {
+    let (socket, target) = listen();
+    let frames = vec![frame(&[(1, 2, 3), (4, 5, 6)], 2)];
+    let options = PlayOptions {
+        protocol: Protocol::E131,
+        target,
+        universe: 7,
+        channel_order: "grb".to_string(),
+        fps: 1000.0,
+        loops: Some(1),
+    };
+
+    play(&frames, None, &options).unwrap();
+
+    for sequence in 1..=2 {
+        let packet = receive(&socket);
+        assert_eq!(packet.len(), 126 + 6);
+        assert_eq!(&packet[4..16], b"ASC-E1.17\0\0\0");
+        assert_eq!(&packet[16..18], &[0x70, 132 - 16]);
+        assert_eq!(packet[111], sequence);
+        assert_eq!(&packet[113..115], &[0, 7]);
+        assert_eq!(&packet[123..125], &[0, 7]);
+        assert_eq!(&packet[125..], &[0, 2, 1, 3, 5, 4, 6]);
+    }
+}
+
+#[test]
+fn test_artnet_universes() {
+    let (socket, target) = listen();
+    // One more LED than fits in a universe.
+    let colors = vec![(9, 9, 9); 171];
+    let frames = vec![frame(&colors, 1)];
+    let options = PlayOptions {
+        protocol: Protocol::ArtNet,
+        target,
+        universe: 0x0102,
+        channel_order: "rgb".to_string(),
+        fps: 1000.0,
+        loops: Some(1),
+    };
+
+    play(&frames, None, &options).unwrap();
+
+    let packet = receive(&socket);
+    assert_eq!(&packet[..12], b"Art-Net\0\0\x50\0\x0e");
+    assert_eq!(packet[12], 1);
+    assert_eq!(&packet[14..16], &[0x02, 0x01]);
+    assert_eq!(&packet[16..18], &[0x01, 0xfe]);
+    assert_eq!(packet.len(), 18 + 510);
+
+    let packet = receive(&socket);
+    assert_eq!(&packet[14..16], &[0x03, 0x01]);
+    assert_eq!(&packet[16..18], &[0, 4]);
+    assert_eq!(&packet[18..], &[9, 9, 9, 0]);
+}
+
+#[test]
+fn test_ddp() {
+    let (socket, target) = listen();
+    // Two packets, as 480 LEDs fit in one.
+    let mut colors = vec![(0, 0, 0); 480];
+    colors.push((1, 2, 3));
+    let frames = vec![frame(&colors, 1)];
+    let options = PlayOptions {
+        protocol: Protocol::Ddp,
+        target,
+        universe: 0,
+        channel_order: "rgb".to_string(),
+        fps: 1000.0,
+        loops: Some(1),
+    };
+
+    play(&frames, None, &options).unwrap();
+
+    let packet = receive(&socket);
+    assert_eq!(&packet[..10], &[0x40, 1, 0x0b, 1, 0, 0, 0, 0, 0x05, 0xa0]);
+    assert_eq!(packet.len(), 10 + 1440);
+
+    let packet = receive(&socket);
+    assert_eq!(&packet[..10], &[0x41, 1, 0x0b, 1, 0, 0, 0x05, 0xa0, 0, 3]);
+    assert_eq!(&packet[10..], &[1, 2, 3]);
+}
+
+#[test]
+fn test_dnrgb() {
+    let (socket, target) = listen();
+    let mut colors = vec![(0, 0, 0); 489];
+    colors.push((1, 2, 3));
+    let frames = vec![frame(&colors, 1)];
+    let options = PlayOptions {
+        protocol: Protocol::Dnrgb,
+        target,
+        universe: 0,
+        channel_order: "bgr".to_string(),
+        fps: 1000.0,
+        loops: Some(1),
+    };
+
+    play(&frames, None, &options).unwrap();
+
+    let packet = receive(&socket);
+    assert_eq!(&packet[..4], &[4, 2, 0, 0]);
+    assert_eq!(packet.len(), 4 + 489 * 3);
+
+    let packet = receive(&socket);
+    assert_eq!(packet, vec![4, 2, 0x01, 0xe9, 3, 2, 1]);
+}
+
+#[test]
+fn test_loop() {
+    let (socket, target) = listen();
+    let frames = vec![frame(&[(1, 1, 1)], 1), frame(&[(2, 2, 2)], 1)];
+    let options = PlayOptions {
+        protocol: Protocol::Drgb,
+        target,
+        universe: 0,
+        channel_order: "rgb".to_string(),
+        fps: 1000.0,
+        loops: Some(3),
+    };
+
+    let player = thread::spawn(move || play(&frames, None, &options));
+
+    for i in 0..6 {
+        let value = i % 2 + 1;
+        assert_eq!(receive(&socket), vec![2, 2, value, value, value]);
+    }
+    player.join().unwrap().unwrap();
+
+    // Nothing is sent after the last loop.
+    socket
+        .set_read_timeout(Some(Duration::from_millis(50)))
+        .unwrap();
+    assert!(socket.recv(&mut [0; 16]).is_err());
+}
+
+#[test]
+fn test_wiring_order() {
+    let (socket, target) = listen();
+    let frames = vec![frame(&[(1, 1, 1), (2, 2, 2), (3, 3, 3)], 1)];
+    // A strip wired from the right end, as in the export.
+    let wiring: Wiring = serde_yaml::from_str(
+        "{panel-width: 3, panel-height: 1, chain: [{x: 0, y: 0, flip: true}]}",
+    )
+    .unwrap();
+    let options = PlayOptions {
+        protocol: Protocol::Drgb,
+        target,
+        universe: 0,
+        channel_order: "rgb".to_string(),
+        fps: 1000.0,
+        loops: Some(1),
+    };
+
+    play(&frames, Some(&wiring), &options).unwrap();
+
+    let export: Export = serde_yaml::from_str("{pixel: rgb888, header: false}").unwrap();
+    let exported = encode(&frames, None, &export, Some(&wiring)).unwrap();
+    let packet = receive(&socket);
+    assert_eq!(&packet[2..], &[3, 3, 3, 2, 2, 2, 1, 1, 1]);
+    assert_eq!(&packet[2..], &exported[..]);
+}
+
+#[test]
+fn test_channel_order() {
+    assert_eq!(channel_order("rgb").unwrap(), [0, 1, 2]);
+    assert_eq!(channel_order("brg").unwrap(), [2, 0, 1]);
+
+    for name in &["", "rg", "rgbw", "rrg", "rgx", "RGB"] {
+        let err = channel_order(name).unwrap_err();
+        assert_eq!(err.to_string(), format!("invalid channel order '{}'", name));
+    }
+}
+
+#[test]
+fn test_invalid_fps() {
+    let frames = vec![frame(&[(1, 1, 1)], 1)];
+
+    for &fps in &[0.0, -1.0, f32::NAN, f32::INFINITY] {
+        let options = PlayOptions {
+            protocol: Protocol::Drgb,
+            target: "127.0.0.1".to_string(),
+            universe: 0,
+            channel_order: "rgb".to_string(),
+            fps,
+            loops: Some(1),
+        };
+        assert!(play(&frames, None, &options).is_err(), "fps {}", fps);
+    }
+}
+
+#[test]
+fn test_universe_out_of_range() {
+    let (_socket, target) = listen();
+    // Two universes from the last one.
+    let frames = vec![frame(&vec![(0, 0, 0); 171], 1)];
+    let options = PlayOptions {
+        protocol: Protocol::E131,
+        target,
+        universe: 63999,
+        channel_order: "rgb".to_string(),
+        fps: 1000.0,
+        loops: Some(1),
+    };
+
+    let err = play(&frames, None, &options).unwrap_err();
+    assert_eq!(err.to_string(), "universe 64000 is out of range");
+}
@@ -0,0 +1,75 @@
+use crate::builder::{ArrayBuilder, Layout};
+use crate::font::FontSet;
+#[cfg(test)]
+use crate::font::test_font_set;
+use crate::model::array::Array;
+use crate::model::color::{Color, ColorSet};
+use crate::model::style::StyleSet;
+use crate::result::Result;
+use crate::source::{Command, Metadata};
+
+/// Builds arrays from bodies with the settings of a source.
+#[derive(Debug, Clone)]
+pub struct Renderer {
+    color_set: ColorSet,
+    style_set: StyleSet,
+    font_set: FontSet,
+    background: Color,
+    panel_width: Option<usize>,
+    fps: Option<f32>,
+}
+
+impl Renderer {
+    pub fn new(meta: &Metadata, font_set: FontSet) -> Result<Renderer> {
+        let color_set = meta.color_set()?;
+        let background = color_set.get("_background")?.clone();
+
+        Ok(Renderer {
+            color_set,
+            style_set: meta.style_set(),
+            font_set,
+            background,
+            panel_width: meta.panel_width(),
+            fps: meta.fps(),
+        })
+    }
+
+    pub fn build<'a>(&self, body: impl Iterator<Item = &'a Command>) -> Result<(Array, Layout)> {
+        let mut builder = ArrayBuilder::new(
+            self.color_set.clone(),
+            self.style_set.clone(),
+            self.font_set.clone(),
+        );
+        builder.set_panel_width(self.panel_width);
+
+        for cmd in body {
+            builder.process(cmd)?;
+        }
+
+        Ok(builder.finish())
+    }
+
+    pub fn foreground(&self) -> Result<&Color> {
+        self.color_set.get("_foreground")
+    }
+
+    pub fn background(&self) -> &Color {
+        &self.background
+    }
+
+    pub fn panel_width(&self) -> Option<usize> {
+        self.panel_width
+    }
+
+    pub fn fps(&self) -> Option<f32> {
+        self.fps
+    }
+}
+
+#[cfg(test)]
+impl Renderer {
+    /// A renderer of `meta` drawing with the font of `test_font_set`.
+    pub fn with_test_font(meta: &Metadata) -> Renderer {
+        Renderer::new(meta, test_font_set()).unwrap()
+    }
+}
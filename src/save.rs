@@ -17,3 +17,18 @@ pub fn save_image(path: impl AsRef<Path>, img: &impl Imageable) -> Result<()> {
 
     Ok(())
 }
+
+/// Pixels of `img` as 8 bit RGBA, row by row.
+pub fn rgba(img: &impl Imageable) -> Vec<u8> {
+    let (w, h) = img.dim();
+    let mut pixels = Vec::with_capacity(w * h * 4);
+
+    for y in 0..h {
+        for x in 0..w {
+            let c = img.get(x, y);
+            pixels.extend(&[c.r(), c.g(), c.b(), c.a()]);
+        }
+    }
+
+    pixels
+}
@@ -0,0 +1,188 @@
+#[cfg(test)]
+mod test;
+
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use deflate::deflate_bytes_zlib;
+use failure::format_err;
+
+use crate::frame::{delays, Frame};
+use crate::model::dot_array::DotArray;
+use crate::model::Imageable;
+use crate::result::Result;
+use crate::save;
+
+const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a];
+
+/// Encodes frames into an animated PNG, one frame at a time.
+///
+/// Each frame after the first only stores the rectangle that changed from the previous one.
+/// `repeat` is the number of times to play the animation again, forever if `None`.
+pub fn save_apng(
+    path: impl AsRef<Path>,
+    frames: &[Frame],
+    dot_arr: &DotArray,
+    fps: Option<f32>,
+    repeat: Option<u16>,
+) -> Result<()> {
+    if frames.len() > 1 && fps.is_none() {
+        return Err(format_err!("apng output of an animation requires meta.fps"));
+    }
+    if frames.is_empty() {
+        return Err(format_err!("no frames to save"));
+    }
+
+    let mut w = BufWriter::new(File::create(path)?);
+    let (width, height) = dot_arr.with_array(frames[0].array.clone()).dim();
+
+    w.write_all(&SIGNATURE)?;
+
+    let mut ihdr = Vec::new();
+    ihdr.extend(&(width as u32).to_be_bytes());
+    ihdr.extend(&(height as u32).to_be_bytes());
+    // 8 bit RGBA, no interlace
+    ihdr.extend(&[8, 6, 0, 0, 0]);
+    write_chunk(&mut w, b"IHDR", &ihdr)?;
+
+    let plays = repeat.map(|count| u32::from(count) + 1).unwrap_or(0);
+    let mut actl = Vec::new();
+    actl.extend(&(frames.len() as u32).to_be_bytes());
+    actl.extend(&plays.to_be_bytes());
+    write_chunk(&mut w, b"acTL", &actl)?;
+
+    let mut sequence = 0u32;
+    let mut previous: Option<Vec<u8>> = None;
+    // Delays are in milliseconds.
+    for (frame, delay) in frames.iter().zip(delays(frames, fps, 1000.0)) {
+        let image = dot_arr.with_array(frame.array.clone());
+        if image.dim() != (width, height) {
+            return Err(format_err!("frame size differs from the first frame"));
+        }
+        let pixels = save::rgba(&image);
+
+        let rect = match &previous {
+            Some(previous) => changed_rect(previous, &pixels, width, height),
+            None => Rect {
+                x: 0,
+                y: 0,
+                width,
+                height,
+            },
+        };
+
+        let mut fctl = Vec::new();
+        fctl.extend(&sequence.to_be_bytes());
+        fctl.extend(&(rect.width as u32).to_be_bytes());
+        fctl.extend(&(rect.height as u32).to_be_bytes());
+        fctl.extend(&(rect.x as u32).to_be_bytes());
+        fctl.extend(&(rect.y as u32).to_be_bytes());
+        fctl.extend(&delay.to_be_bytes());
+        fctl.extend(&1000u16.to_be_bytes());
+        // dispose: none, blend: source
+        fctl.extend(&[0, 0]);
+        write_chunk(&mut w, b"fcTL", &fctl)?;
+        sequence += 1;
+
+        let data = deflate_bytes_zlib(&scanlines(&pixels, width, &rect));
+        if previous.is_none() {
+            write_chunk(&mut w, b"IDAT", &data)?;
+        } else {
+            let mut fdat = sequence.to_be_bytes().to_vec();
+            fdat.extend(&data);
+            write_chunk(&mut w, b"fdAT", &fdat)?;
+            sequence += 1;
+        }
+
+        previous = Some(pixels);
+    }
+
+    write_chunk(&mut w, b"IEND", &[])?;
+    w.flush()?;
+
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Rect {
+    x: usize,
+    y: usize,
+    width: usize,
+    height: usize,
+}
+
+/// Smallest rectangle containing all changed pixels.
+/// A frame without changes still needs a rectangle, so a single pixel is used.
+fn changed_rect(previous: &[u8], current: &[u8], width: usize, height: usize) -> Rect {
+    let (mut x0, mut y0, mut x1, mut y1) = (width, height, 0, 0);
+
+    for y in 0..height {
+        for x in 0..width {
+            let i = (y * width + x) * 4;
+            if previous[i..i + 4] != current[i..i + 4] {
+                x0 = x0.min(x);
+                y0 = y0.min(y);
+                x1 = x1.max(x + 1);
+                y1 = y1.max(y + 1);
+            }
+        }
+    }
+
+    if x0 >= x1 {
+        return Rect {
+            x: 0,
+            y: 0,
+            width: 1,
+            height: 1,
+        };
+    }
+
+    Rect {
+        x: x0,
+        y: y0,
+        width: x1 - x0,
+        height: y1 - y0,
+    }
+}
+
+/// Rows of `rect`, each prefixed with filter type 0.
+fn scanlines(pixels: &[u8], width: usize, rect: &Rect) -> Vec<u8> {
+    let mut data = Vec::with_capacity((rect.width * 4 + 1) * rect.height);
+
+    for y in rect.y..rect.y + rect.height {
+        let start = (y * width + rect.x) * 4;
+        data.push(0);
+        data.extend(&pixels[start..start + rect.width * 4]);
+    }
+
+    data
+}
+
+fn write_chunk(w: &mut impl Write, kind: &[u8; 4], data: &[u8]) -> Result<()> {
+    w.write_all(&(data.len() as u32).to_be_bytes())?;
+    w.write_all(kind)?;
+    w.write_all(data)?;
+
+    let crc = crc32(kind.iter().chain(data));
+    w.write_all(&crc.to_be_bytes())?;
+
+    Ok(())
+}
+
+fn crc32<'a>(bytes: impl Iterator<Item = &'a u8>) -> u32 {
+    let mut crc = !0u32;
+
+    for &b in bytes {
+        crc ^= u32::from(b);
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                0xedb8_8320 ^ (crc >> 1)
+            } else {
+                crc >> 1
+            };
+        }
+    }
+
+    !crc
+}
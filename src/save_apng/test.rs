@@ -0,0 +1,71 @@
+use std::env;
+use std::fs;
+
+use super::*;
+use crate::model::array::Array;
+use crate::model::color::Color;
+
+fn chunks(data: &[u8]) -> Vec<(String, Vec<u8>)> {
+    let mut chunks = Vec::new();
+    let mut rest = &data[8..];
+
+    while !rest.is_empty() {
+        let len = u32::from_be_bytes([rest[0], rest[1], rest[2], rest[3]]) as usize;
+        let kind = String::from_utf8(rest[4..8].to_vec()).unwrap();
+        chunks.push((kind, rest[8..8 + len].to_vec()));
+        rest = &rest[12 + len..];
+    }
+
+    chunks
+}
+
+#[test]
+fn test_crc32() {
+    assert_eq!(crc32(b"IEND".iter()), 0xae42_6082);
+}
+
+#[test]
+fn test_delta_frame() {
+    let black = Color::new(0u8, 0u8, 0u8, 255u8);
+    let white = Color::new(255u8, 255u8, 255u8, 255u8);
+
+    let first = Array::filled(4, 3, &black);
+    let mut second = Array::new(3);
+    for x in 0..4 {
+        let c = if x == 2 { &white } else { &black };
+        second.add_line(vec![black.clone(), c.clone(), black.clone()]);
+    }
+
+    let frames = vec![
+        Frame {
+            array: first.clone(),
+            hold: 2,
+        },
+        Frame {
+            array: second,
+            hold: 1,
+        },
+    ];
+    let dot_arr = DotArray::new(first, Array::filled(1, 1, &white));
+
+    let path = env::temp_dir().join(format!("led-image-test-{}.png", std::process::id()));
+    save_apng(&path, &frames, &dot_arr, Some(10.0), None).unwrap();
+    let data = fs::read(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+
+    let kinds = chunks(&data)
+        .iter()
+        .map(|(kind, _)| kind.clone())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        kinds,
+        vec!["IHDR", "acTL", "fcTL", "IDAT", "fcTL", "fdAT", "IEND"]
+    );
+
+    let fctl = &chunks(&data)[4].1;
+    let field = |i: usize| u32::from_be_bytes([fctl[i], fctl[i + 1], fctl[i + 2], fctl[i + 3]]);
+    // width, height, x, y of the changed rectangle
+    assert_eq!((field(4), field(8), field(12), field(16)), (1, 1, 2, 1));
+    // 100 ms
+    assert_eq!(&fctl[20..24], &[0, 100, 3, 232]);
+}
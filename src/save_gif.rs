@@ -0,0 +1,124 @@
+#[cfg(test)]
+mod test;
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+
+use color_quant::NeuQuant;
+use failure::format_err;
+use gif::{Encoder, Repeat, SetParameter};
+
+use crate::frame::{delays, Frame};
+use crate::model::color::Color;
+use crate::model::dot_array::DotArray;
+use crate::model::Imageable;
+use crate::result::Result;
+
+/// Encodes frames into an animated GIF, one frame at a time.
+///
+/// The global palette is made of the colors of the dot table, which is usually small.
+/// Transparent pixels of the dot are drawn over black.
+/// `repeat` is the number of times to play the animation again, forever if `None`.
+pub fn save_gif(
+    path: impl AsRef<Path>,
+    frames: &[Frame],
+    dot_arr: &DotArray,
+    fps: Option<f32>,
+    repeat: Option<u16>,
+) -> Result<()> {
+    if frames.len() > 1 && fps.is_none() {
+        return Err(format_err!("gif output of an animation requires meta.fps"));
+    }
+
+    let mut table = dot_arr.with_array(dot_arr.array().clone());
+    for frame in frames {
+        table = table.with_array(frame.array.clone());
+    }
+
+    let palette = Palette::new(table.dot_colors().iter().map(Color::rgb).collect());
+
+    let (w, h) = table.dim();
+    if w > u16::max_value() as usize || h > u16::max_value() as usize {
+        return Err(format_err!("image size {}x{} is too large for gif", w, h));
+    }
+
+    let file = BufWriter::new(File::create(path)?);
+    let mut encoder = Encoder::new(file, w as u16, h as u16, &palette.colors)?;
+    encoder.set(match repeat {
+        Some(count) => Repeat::Finite(count),
+        None => Repeat::Infinite,
+    })?;
+
+    // Delays are in centiseconds.
+    for (frame, delay) in frames.iter().zip(delays(frames, fps, 100.0)) {
+        if delay == 0 && frames.len() > 1 {
+            continue;
+        }
+
+        let image = table.with_array(frame.array.clone());
+        let (w, h) = image.dim();
+        let mut buffer = Vec::with_capacity(w * h);
+        for y in 0..h {
+            for x in 0..w {
+                buffer.push(palette.index(&image.get(x, y)));
+            }
+        }
+
+        encoder.write_frame(&gif::Frame {
+            delay,
+            width: w as u16,
+            height: h as u16,
+            buffer: Cow::Owned(buffer),
+            ..gif::Frame::default()
+        })?;
+    }
+
+    Ok(())
+}
+
+struct Palette {
+    colors: Vec<u8>,
+    indices: HashMap<[u8; 3], u8>,
+}
+
+impl Palette {
+    /// Makes a palette of at most 256 entries, quantizing `colors` only if there are more.
+    fn new(mut colors: Vec<[u8; 3]>) -> Palette {
+        colors.sort();
+        colors.dedup();
+
+        if colors.len() <= 256 {
+            let indices = colors
+                .iter()
+                .enumerate()
+                .map(|(i, c)| (*c, i as u8))
+                .collect();
+            return Palette {
+                colors: colors.concat(),
+                indices,
+            };
+        }
+
+        let pixels = colors
+            .iter()
+            .flat_map(|c| vec![c[0], c[1], c[2], 255])
+            .collect::<Vec<_>>();
+        let quant = NeuQuant::new(10, 256, &pixels);
+        let indices = colors
+            .iter()
+            .map(|c| (*c, quant.index_of(&[c[0], c[1], c[2], 255]) as u8))
+            .collect();
+
+        Palette {
+            colors: quant.color_map_rgb(),
+            indices,
+        }
+    }
+
+    fn index(&self, color: &Color) -> u8 {
+        self.indices[&color.rgb()]
+    }
+}
@@ -0,0 +1,40 @@
+use std::env;
+use std::fs;
+
+use gif::Decoder;
+
+use super::*;
+use crate::model::array::Array;
+
+#[test]
+fn test_delays_follow_holds() {
+    let black = Color::new(0u8, 0u8, 0u8, 255u8);
+    let red = Color::new(255u8, 0u8, 0u8, 255u8);
+    let white = Color::new(255u8, 255u8, 255u8, 255u8);
+
+    let frames = vec![
+        Frame {
+            array: Array::filled(2, 2, &black),
+            hold: 3,
+        },
+        Frame {
+            array: Array::filled(2, 2, &red),
+            hold: 1,
+        },
+    ];
+    let dot_arr = DotArray::new(Array::filled(2, 2, &black), Array::filled(1, 1, &white));
+
+    let path = env::temp_dir().join(format!("led-image-test-{}.gif", std::process::id()));
+    save_gif(&path, &frames, &dot_arr, Some(30.0), None).unwrap();
+
+    let mut reader = Decoder::new(File::open(&path).unwrap())
+        .read_info()
+        .unwrap();
+    let mut delays = Vec::new();
+    while let Some(frame) = reader.read_next_frame().unwrap() {
+        delays.push(frame.delay);
+    }
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(delays, vec![10, 3]);
+}
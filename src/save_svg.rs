@@ -0,0 +1,147 @@
+#[cfg(test)]
+mod test;
+
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+use failure::format_err;
+
+use crate::frame::Frame;
+use crate::model::array::Array;
+use crate::model::color::Color;
+use crate::result::Result;
+use crate::source::{Shape, SvgDot};
+
+/// Saves `array` as an SVG image.
+pub fn save_svg(path: impl AsRef<Path>, array: &Array, dot: &SvgDot) -> Result<()> {
+    let symbol = symbol(dot)?;
+    fs::write(path, document(array, &symbol, dot.cell()))?;
+
+    Ok(())
+}
+
+/// Saves frames as numbered SVG files. A held frame is saved repeatedly.
+pub fn save_svg_frames(dir_path: impl AsRef<Path>, frames: &[Frame], dot: &SvgDot) -> Result<()> {
+    let _ = fs::remove_dir_all(&dir_path);
+    fs::create_dir_all(&dir_path)?;
+
+    let symbol = symbol(dot)?;
+    let mut index = 0;
+
+    for frame in frames {
+        let svg = document(&frame.array, &symbol, dot.cell());
+        for _ in 0..frame.hold {
+            fs::write(dir_path.as_ref().join(format!("{:08}.svg", index)), &svg)?;
+            index += 1;
+        }
+    }
+
+    Ok(())
+}
+
+/// Makes a document with a `<use>` of the dot symbol for each cell.
+fn document(array: &Array, symbol: &str, cell: f32) -> String {
+    let (width, height) = (array.width(), array.height());
+    let mut svg = String::new();
+
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink" width="{}" height="{}" viewBox="0 0 {} {}">"#,
+        width as f32 * cell,
+        height as f32 * cell,
+        width as f32 * cell,
+        height as f32 * cell
+    );
+    let _ = writeln!(svg, "<defs>{}</defs>", symbol);
+
+    for (x, y, color) in array.iter() {
+        let _ = write!(
+            svg,
+            r##"<use xlink:href="#dot" href="#dot" x="{}" y="{}" width="{}" height="{}" color="{}""##,
+            x as f32 * cell,
+            y as f32 * cell,
+            cell,
+            cell,
+            rgb(color)
+        );
+        if color.a::<u8>() < 255 {
+            let _ = write!(svg, r#" opacity="{}""#, color.a);
+        }
+        svg.push_str("/>\n");
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// The dot as a `<symbol>`, from the template if there is one.
+fn symbol(dot: &SvgDot) -> Result<String> {
+    if let Some(path) = dot.template() {
+        let template =
+            fs::read_to_string(path).map_err(|e| format_err!("cannot open '{}': {}", path, e))?;
+        let (view_box, content) =
+            parse_template(&template).map_err(|e| format_err!("{}: {}", path, e))?;
+
+        return Ok(format!(
+            r#"<symbol id="dot" viewBox="{}">{}</symbol>"#,
+            view_box, content
+        ));
+    }
+
+    let size = dot.size();
+    let shape = match dot.shape() {
+        Shape::Circle => format!(
+            r#"<circle cx="0.5" cy="0.5" r="{}" fill="currentColor"/>"#,
+            size / 2.0
+        ),
+        Shape::Square => format!(
+            r#"<rect x="{}" y="{}" width="{}" height="{}" fill="currentColor"/>"#,
+            (1.0 - size) / 2.0,
+            (1.0 - size) / 2.0,
+            size,
+            size
+        ),
+    };
+
+    Ok(format!(
+        r#"<symbol id="dot" viewBox="0 0 1 1">{}</symbol>"#,
+        shape
+    ))
+}
+
+/// Splits an SVG file into the `viewBox` of the root element and its content.
+fn parse_template(template: &str) -> Result<(String, String)> {
+    let start = template
+        .find("<svg")
+        .ok_or_else(|| format_err!("no <svg> element"))?;
+    let open_end = start
+        + template[start..]
+            .find('>')
+            .ok_or_else(|| format_err!("unclosed <svg> tag"))?;
+    let close = template
+        .rfind("</svg>")
+        .ok_or_else(|| format_err!("no </svg> tag"))?;
+
+    let tag = &template[start..open_end];
+    let view_box = tag
+        .find("viewBox=")
+        .and_then(|i| {
+            let rest = &tag[i + "viewBox=".len()..];
+            let quote = rest.chars().next()?;
+            let rest = &rest[1..];
+            rest.find(quote).map(|end| rest[..end].to_string())
+        })
+        .ok_or_else(|| format_err!("the <svg> element needs a viewBox"))?;
+
+    Ok((view_box, template[open_end + 1..close].trim().to_string()))
+}
+
+fn rgb(color: &Color) -> String {
+    format!(
+        "#{:02x}{:02x}{:02x}",
+        color.r::<u8>(),
+        color.g::<u8>(),
+        color.b::<u8>()
+    )
+}
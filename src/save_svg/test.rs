@@ -0,0 +1,36 @@
+use super::*;
+
+#[test]
+fn test_one_use_per_cell() {
+    let red = Color::new(255u8, 0u8, 0u8, 255u8);
+    let dim = Color::new(0u8, 0u8, 255u8, 128u8);
+    let mut array = Array::new(1);
+    array.add_line(vec![red]);
+    array.add_line(vec![dim]);
+
+    let svg = document(&array, &symbol(&SvgDot::default()).unwrap(), 10.0);
+
+    assert_eq!(svg.matches("<use ").count(), 2);
+    assert!(svg.contains(r#"viewBox="0 0 20 10""#));
+    assert!(svg.contains(r##"x="0" y="0" width="10" height="10" color="#ff0000"/>"##));
+    assert!(svg.contains(r##"x="10" y="0" width="10" height="10" color="#0000ff" opacity="##));
+    assert!(svg.contains(r#"<circle cx="0.5" cy="0.5" r="0.4" fill="currentColor"/>"#));
+}
+
+#[test]
+fn test_parse_template() {
+    let template = r#"<?xml version="1.0"?>
+<svg xmlns="http://www.w3.org/2000/svg" viewBox='0 0 16 16'>
+  <circle cx="8" cy="8" r="7" fill="currentColor"/>
+</svg>
+"#;
+
+    let (view_box, content) = parse_template(template).unwrap();
+    assert_eq!(view_box, "0 0 16 16");
+    assert_eq!(
+        content,
+        r#"<circle cx="8" cy="8" r="7" fill="currentColor"/>"#
+    );
+
+    assert!(parse_template("<svg><rect/></svg>").is_err());
+}
@@ -0,0 +1,116 @@
+#[cfg(test)]
+mod test;
+
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use failure::format_err;
+
+use crate::frame::Frame;
+use crate::model::dot_array::DotArray;
+use crate::model::Imageable;
+use crate::result::Result;
+use crate::save;
+
+/// Writes frames as a YUV4MPEG2 video with 4:2:0 chroma, one frame at a time.
+///
+/// Colors are converted with BT.601 in limited range, and transparent pixels
+/// of the dot are drawn over black. A held frame is written repeatedly.
+pub fn save_y4m(
+    path: impl AsRef<Path>,
+    frames: &[Frame],
+    dot_arr: &DotArray,
+    fps: Option<f32>,
+) -> Result<()> {
+    let fps = fps.ok_or_else(|| format_err!("y4m output requires meta.fps"))?;
+    let first = frames
+        .first()
+        .ok_or_else(|| format_err!("no frames to save"))?;
+    let (width, height) = dot_arr.with_array(first.array.clone()).dim();
+
+    let mut w = BufWriter::new(File::create(path)?);
+    let (num, den) = frame_rate(fps);
+    writeln!(
+        w,
+        "YUV4MPEG2 W{} H{} F{}:{} Ip A1:1 C420jpeg",
+        width, height, num, den
+    )?;
+
+    for frame in frames {
+        let image = dot_arr.with_array(frame.array.clone());
+        if image.dim() != (width, height) {
+            return Err(format_err!("frame size differs from the first frame"));
+        }
+
+        let planes = yuv420(&save::rgba(&image), width, height);
+        for _ in 0..frame.hold {
+            w.write_all(b"FRAME\n")?;
+            w.write_all(&planes)?;
+        }
+    }
+
+    w.flush()?;
+
+    Ok(())
+}
+
+/// Frame rate as a reduced fraction, keeping three decimal places.
+fn frame_rate(fps: f32) -> (u32, u32) {
+    let (mut num, mut den) = ((fps * 1000.0).round() as u32, 1000);
+
+    let (mut a, mut b) = (num, den);
+    while b != 0 {
+        let r = a % b;
+        a = b;
+        b = r;
+    }
+    if a > 1 {
+        num /= a;
+        den /= a;
+    }
+
+    (num, den)
+}
+
+/// Y, Cb and Cr planes of RGBA `pixels`. Chroma is averaged over 2x2 blocks.
+fn yuv420(pixels: &[u8], width: usize, height: usize) -> Vec<u8> {
+    let (cw, ch) = (width / 2 + width % 2, height / 2 + height % 2);
+    let mut y_plane = Vec::with_capacity(width * height);
+    let mut cb_sum = vec![(0.0, 0); cw * ch];
+    let mut cr_sum = vec![0.0; cw * ch];
+
+    for y in 0..height {
+        for x in 0..width {
+            let p = &pixels[(y * width + x) * 4..][..4];
+            let a = f32::from(p[3]) / 255.0;
+            let (r, g, b) = (
+                f32::from(p[0]) * a / 255.0,
+                f32::from(p[1]) * a / 255.0,
+                f32::from(p[2]) * a / 255.0,
+            );
+
+            y_plane.push((16.0 + 65.481 * r + 128.553 * g + 24.966 * b).round() as u8);
+
+            let c = (y / 2) * cw + x / 2;
+            cb_sum[c].0 += 128.0 - 37.797 * r - 74.203 * g + 112.0 * b;
+            cb_sum[c].1 += 1;
+            cr_sum[c] += 128.0 + 112.0 * r - 93.786 * g - 18.214 * b;
+        }
+    }
+
+    let mut planes = y_plane;
+    planes.extend(
+        cb_sum
+            .iter()
+            .map(|&(sum, n)| (sum / n as f32).round() as u8),
+    );
+    planes.extend(
+        cr_sum
+            .iter()
+            .zip(&cb_sum)
+            .map(|(sum, &(_, n))| (sum / n as f32).round() as u8),
+    );
+
+    planes
+}
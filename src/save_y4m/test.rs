@@ -0,0 +1,42 @@
+use std::env;
+use std::fs;
+
+use super::*;
+use crate::model::array::Array;
+use crate::model::color::Color;
+
+#[test]
+fn test_frame_rate() {
+    assert_eq!(frame_rate(30.0), (30, 1));
+    assert_eq!(frame_rate(29.97), (2997, 100));
+    assert_eq!(frame_rate(12.5), (25, 2));
+}
+
+#[test]
+fn test_bt601() {
+    let white = [255, 255, 255, 255];
+    let red = [255, 0, 0, 255];
+
+    assert_eq!(yuv420(&white, 1, 1), vec![235, 128, 128]);
+    assert_eq!(yuv420(&red, 1, 1), vec![81, 90, 240]);
+}
+
+#[test]
+fn test_held_frames() {
+    let white = Color::new(255u8, 255u8, 255u8, 255u8);
+    let frames = vec![Frame {
+        array: Array::filled(3, 3, &white),
+        hold: 2,
+    }];
+    let dot_arr = DotArray::new(Array::filled(3, 3, &white), Array::filled(1, 1, &white));
+
+    let path = env::temp_dir().join(format!("led-image-test-{}.y4m", std::process::id()));
+    save_y4m(&path, &frames, &dot_arr, Some(25.0)).unwrap();
+    let data = fs::read(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+
+    let header = b"YUV4MPEG2 W3 H3 F25:1 Ip A1:1 C420jpeg\n";
+    assert!(data.starts_with(header));
+    // 9 luma and 2x2 of each chroma per frame
+    assert_eq!(data.len(), header.len() + 2 * (6 + 9 + 4 + 4));
+}
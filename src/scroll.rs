@@ -1,40 +1,184 @@
-use std::fs;
-use std::path::{Path, PathBuf};
+#[cfg(test)]
+mod test;
 
-use rayon::prelude::*;
+use failure::format_err;
 
+use crate::builder::Layout;
+use crate::effect;
+use crate::frame::Frame;
+use crate::model::array::Array;
 use crate::model::color::Color;
-use crate::model::dot_array::DotArray;
+use crate::model::command::Align;
 use crate::result::Result;
-use crate::save;
-use crate::source::Scroll;
+use crate::source::{Direction, Scroll, Step};
 
-pub fn save_scroll(
-    dir_path: impl AsRef<Path>,
-    dot_arr: &DotArray,
+/// Frames of `array` moving through a view of `scroll`.
+///
+/// A vertical scroll moves the single row of `array`, which must fit in the view width.
+pub fn scroll_frames(
+    array: &Array,
+    layout: &Layout,
     scroll: &Scroll,
     background: &Color,
-) -> Result<()> {
-    let _ = fs::remove_dir_all(&dir_path);
-    fs::create_dir_all(&dir_path)?;
+    fps: Option<f32>,
+) -> Result<Vec<Frame>> {
+    let width = scroll
+        .width()
+        .ok_or_else(|| format_err!("scroll requires width"))?;
+    let height = scroll.height().unwrap_or_else(|| array.height());
+    let speed = scroll.speed(fps)?;
+    let blend = scroll.step() == Step::Blend;
 
-    let dot_width = dot_arr.dim_in_dot().0;
-    let width = scroll.width() as isize;
+    let direction = scroll.direction();
+    let horizontal = direction == Direction::Left || direction == Direction::Right;
+    let reverse = direction == Direction::Right || direction == Direction::Down;
 
-    let (start, end) = (-width, (dot_width) as isize + width);
-    let offsets: Vec<(PathBuf, DotArray)> = (start..end)
+    if scroll.wrap() && scroll.has_range() {
+        return Err(format_err!("start and end cannot be used with wrap"));
+    }
+
+    if !horizontal && !layout.pauses.is_empty() {
+        return Err(format_err!("pause is not supported in vertical scroll"));
+    }
+
+    if !horizontal && array.width() > width {
+        return Err(format_err!(
+            "content is {} wide, wider than the vertical scroll of width {}",
+            array.width(),
+            width
+        ));
+    }
+
+    let (content_len, view) = if horizontal {
+        (array.width(), width)
+    } else {
+        (array.height(), height)
+    };
+
+    let (mut positions, period) = if scroll.wrap() {
+        let period = content_len + scroll.gap().unwrap_or(view);
+        if period == 0 {
+            return Err(format_err!("wrap requires content or a gap"));
+        }
+        (wrap_positions(period, speed, reverse), Some(period))
+    } else {
+        let from = scroll.start_offset(content_len, view, reverse);
+        let to = scroll.end_offset(content_len, view, reverse);
+        (positions(from, to, speed), None)
+    };
+
+    let prepare = |array: &Array| {
+        let source = if horizontal {
+            array.clone()
+        } else {
+            array.slice(0, width, background)
+        };
+
+        match period {
+            Some(period) if horizontal => {
+                source.slice(0, period, background).tile(view / period + 2)
+            }
+            Some(period) => source
+                .slice_vertical(0, period, background)
+                .tile_vertical(view / period + 2),
+            None => source,
+        }
+    };
+
+    for pause in &layout.pauses {
+        let mut target = match pause.align {
+            Align::Left => pause.column as f32,
+            Align::Center => pause.column as f32 - (width / 2) as f32,
+            Align::Right => pause.column as f32 - width as f32,
+        };
+        if let Some(period) = period {
+            target = target.rem_euclid(period as f32);
+        }
+        insert_pause(&mut positions, target, pause.hold.frames(fps)?, reverse);
+    }
+
+    let view_at = |source: &Array, offset: f32| match (horizontal, blend) {
+        (true, true) => source.slice_blend(offset, width, background),
+        (true, false) => source.slice(offset.floor() as isize, width, background),
+        (false, true) => source.slice_vertical_blend(offset, height, background),
+        (false, false) => source.slice_vertical(offset.floor() as isize, height, background),
+    };
+
+    if layout.effects.is_empty() {
+        let source = prepare(array);
+        let frames = positions
+            .into_iter()
+            .map(|(offset, hold)| Frame {
+                array: view_at(&source, offset),
+                hold,
+            })
+            .collect();
+
+        return Ok(frames);
+    }
+
+    // Effects change colors over time, so every held frame is rendered on its own.
+    let fps = fps.ok_or_else(|| format_err!("text effects require meta.fps"))?;
+    let frames = positions
+        .into_iter()
+        .flat_map(|(offset, hold)| (0..hold).map(move |_| offset))
         .enumerate()
         .map(|(i, offset)| {
-            let file_name = format!("{:08}.png", i);
-            let path = dir_path.as_ref().join(file_name);
-            let arr_slice = dot_arr.slice(offset, scroll.width(), background);
-            (path, arr_slice)
+            let source = prepare(&effect::apply(array, &layout.effects, i as f32 / fps));
+            Frame {
+                array: view_at(&source, offset),
+                hold: 1,
+            }
         })
         .collect();
 
-    offsets
-        .into_par_iter()
-        .try_for_each(|(path, arr_slice)| save::save_image(path, &arr_slice))?;
+    Ok(frames)
+}
+
+/// Offsets covering exactly one `period` of a wrapped scroll, each held one frame.
+/// The speed is adjusted slightly so that the period is a whole number of frames.
+fn wrap_positions(period: usize, speed: f32, reverse: bool) -> Vec<(f32, usize)> {
+    let count = ((period as f32 / speed).round() as usize).max(1);
+    let step = period as f32 / count as f32;
+
+    (0..count)
+        .map(|i| {
+            let d = i as f32 * step;
+            if reverse {
+                (period as f32 - d, 1)
+            } else {
+                (d, 1)
+            }
+        })
+        .collect()
+}
+
+/// Offsets from `from` to `to` (inclusive when reached) advancing `speed` per frame,
+/// each held one frame.
+fn positions(from: isize, to: isize, speed: f32) -> Vec<(f32, usize)> {
+    let distance = (to - from).abs() as f32;
+    let count = (distance / speed).floor() as usize + 1;
+    let sign = if to < from { -1.0 } else { 1.0 };
+
+    (0..count)
+        .map(|i| (from as f32 + sign * i as f32 * speed, 1))
+        .collect()
+}
+
+/// Holds the scroll at `target` for `frames` more frames,
+/// adding a frame at `target` if the scroll steps over it.
+fn insert_pause(positions: &mut Vec<(f32, usize)>, target: f32, frames: usize, reverse: bool) {
+    let found = positions.iter().position(|&(offset, _)| {
+        if reverse {
+            offset <= target
+        } else {
+            offset >= target
+        }
+    });
 
-    Ok(())
+    match found {
+        Some(i) if positions[i].0 == target => positions[i].1 += frames,
+        Some(i) => positions.insert(i, (target, 1 + frames)),
+        None => {}
+    }
 }
@@ -0,0 +1,309 @@
+use super::*;
+use crate::builder::{Hold, PauseMark};
+
+fn black() -> Color {
+    Color::new(0u8, 0u8, 0u8, 255u8)
+}
+
+/// An array whose red is its column + 1 and green its row + 1, so 0 is the background.
+fn grid(w: usize, h: usize) -> Array {
+    let mut array = Array::with_dim(w, h);
+    for x in 0..w {
+        array.add_line(
+            (0..h)
+                .map(|y| Color::new(x as u8 + 1, y as u8 + 1, 0u8, 255u8))
+                .collect(),
+        );
+    }
+    array
+}
+
+fn scroll(yaml: &str) -> Scroll {
+    serde_yaml::from_str(yaml).unwrap()
+}
+
+fn frames(array: &Array, yaml: &str) -> Vec<Frame> {
+    scroll_frames(array, &Layout::default(), &scroll(yaml), &black(), None).unwrap()
+}
+
+/// Reds of the top row of a frame.
+fn row(frame: &Frame) -> Vec<u8> {
+    (0..frame.array.width())
+        .map(|x| frame.array.get_line(x).unwrap()[0].r())
+        .collect()
+}
+
+/// Greens of the left column of a frame.
+fn column(frame: &Frame) -> Vec<u8> {
+    frame
+        .array
+        .get_line(0)
+        .unwrap()
+        .iter()
+        .map(|c| c.g())
+        .collect()
+}
+
+#[test]
+fn test_left() {
+    let frames = frames(&grid(3, 1), "{width: 2}");
+
+    let rows: Vec<_> = frames.iter().map(row).collect();
+    assert_eq!(rows[0], [0, 0]);
+    assert_eq!(rows[1], [0, 1]);
+    assert_eq!(rows[2], [1, 2]);
+    assert_eq!(rows[4], [3, 0]);
+}
+
+#[test]
+fn test_right() {
+    let frames = frames(&grid(3, 1), "{width: 2, direction: right}");
+
+    let rows: Vec<_> = frames.iter().map(row).collect();
+    assert_eq!(rows[0], [0, 0]);
+    assert_eq!(rows[1], [3, 0]);
+    assert_eq!(rows[3], [1, 2]);
+    assert_eq!(rows[4], [0, 1]);
+}
+
+#[test]
+fn test_up() {
+    let frames = frames(&grid(2, 3), "{width: 2, height: 2, direction: up}");
+
+    assert!(frames.iter().all(|f| f.array.width() == 2));
+    assert!(frames.iter().all(|f| f.array.height() == 2));
+    let columns: Vec<_> = frames.iter().map(column).collect();
+    assert_eq!(columns[0], [0, 0]);
+    assert_eq!(columns[1], [0, 1]);
+    assert_eq!(columns[2], [1, 2]);
+    assert_eq!(columns[3], [2, 3]);
+}
+
+#[test]
+fn test_down() {
+    let frames = frames(&grid(1, 3), "{width: 1, height: 2, direction: down}");
+
+    let columns: Vec<_> = frames.iter().map(column).collect();
+    assert_eq!(columns[0], [0, 0]);
+    assert_eq!(columns[1], [3, 0]);
+    assert_eq!(columns[3], [1, 2]);
+    assert_eq!(columns[4], [0, 1]);
+}
+
+#[test]
+fn test_vertical_narrower_content_is_padded() {
+    let frames = frames(&grid(1, 2), "{width: 3, height: 2, direction: up}");
+
+    assert_eq!(row(&frames[2]), [1, 0, 0]);
+}
+
+#[test]
+fn test_vertical_wider_content() {
+    let result = scroll_frames(
+        &grid(3, 2),
+        &Layout::default(),
+        &scroll("{width: 2, direction: up}"),
+        &black(),
+        None,
+    );
+
+    assert_eq!(
+        result.unwrap_err().to_string(),
+        "content is 3 wide, wider than the vertical scroll of width 2"
+    );
+}
+
+#[test]
+fn test_positions_fractional_speed() {
+    assert_eq!(positions(0, 3, 1.5), [(0.0, 1), (1.5, 1), (3.0, 1)]);
+    assert_eq!(positions(0, 2, 0.75), [(0.0, 1), (0.75, 1), (1.5, 1)]);
+    assert_eq!(positions(2, -1, 1.5), [(2.0, 1), (0.5, 1), (-1.0, 1)]);
+}
+
+#[test]
+fn test_snap_and_blend() {
+    let snap = frames(&grid(2, 1), "{width: 1, speed: 0.5}");
+    let blend = frames(&grid(2, 1), "{width: 1, speed: 0.5, step: blend}");
+
+    // Offsets -1, -0.5, 0, 0.5, ... with the content at columns 0 and 1.
+    assert_eq!(snap.len(), blend.len());
+    assert_eq!(row(&snap[1]), [0]);
+    assert_eq!(row(&snap[3]), [1]);
+
+    let reds: Vec<f32> = blend
+        .iter()
+        .map(|f| f.array.get_line(0).unwrap()[0].r())
+        .collect();
+    assert!((reds[1] - 0.5 / 255.0).abs() < 1e-6);
+    assert!((reds[2] - 1.0 / 255.0).abs() < 1e-6);
+    assert!((reds[3] - 1.5 / 255.0).abs() < 1e-6);
+}
+
+fn paused(column: usize, align: Align, frames: usize) -> Layout {
+    Layout {
+        pauses: vec![PauseMark {
+            column,
+            hold: Hold::Frames(frames),
+            align,
+        }],
+        ..Layout::default()
+    }
+}
+
+#[test]
+fn test_insert_pause() {
+    let mut exact = positions(0, 3, 1.0);
+    insert_pause(&mut exact, 2.0, 3, false);
+    assert_eq!(exact, [(0.0, 1), (1.0, 1), (2.0, 4), (3.0, 1)]);
+
+    let mut stepped_over = positions(0, 4, 1.5);
+    insert_pause(&mut stepped_over, 2.0, 3, false);
+    assert_eq!(stepped_over, [(0.0, 1), (1.5, 1), (2.0, 4), (3.0, 1)]);
+
+    let mut reverse = positions(3, 0, 1.0);
+    insert_pause(&mut reverse, 1.5, 2, true);
+    assert_eq!(reverse, [(3.0, 1), (2.0, 1), (1.5, 3), (1.0, 1), (0.0, 1)]);
+
+    let mut beyond = positions(0, 3, 1.0);
+    insert_pause(&mut beyond, 5.0, 2, false);
+    assert_eq!(beyond, positions(0, 3, 1.0));
+}
+
+#[test]
+fn test_pause_align() {
+    let holds = |align| {
+        let layout = paused(2, align, 2);
+        let frames =
+            scroll_frames(&grid(4, 1), &layout, &scroll("{width: 2}"), &black(), None).unwrap();
+        frames
+            .iter()
+            .map(|f| (row(f), f.hold))
+            .filter(|&(_, hold)| hold > 1)
+            .collect::<Vec<_>>()
+    };
+
+    // The marked column stops at the left edge, the center or just past the right edge.
+    assert_eq!(holds(Align::Left), [(vec![3, 4], 3)]);
+    assert_eq!(holds(Align::Center), [(vec![2, 3], 3)]);
+    assert_eq!(holds(Align::Right), [(vec![1, 2], 3)]);
+}
+
+#[test]
+fn test_pause_vertical() {
+    let result = scroll_frames(
+        &grid(1, 2),
+        &paused(0, Align::Left, 1),
+        &scroll("{width: 1, direction: up}"),
+        &black(),
+        None,
+    );
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_wrap_positions() {
+    assert_eq!(
+        wrap_positions(3, 1.0, false),
+        [(0.0, 1), (1.0, 1), (2.0, 1)]
+    );
+    assert_eq!(wrap_positions(3, 1.0, true), [(3.0, 1), (2.0, 1), (1.0, 1)]);
+    // 4 columns at 1.5 round to 3 frames of 4/3 columns.
+    let positions = wrap_positions(4, 1.5, false);
+    assert_eq!(positions.len(), 3);
+    assert!((positions[1].0 - 4.0 / 3.0).abs() < 1e-6);
+}
+
+#[test]
+fn test_wrap_gap() {
+    // The gap defaults to the view, so the content leaves before it comes again.
+    let rows: Vec<_> = frames(&grid(2, 1), "{width: 2, wrap: true}")
+        .iter()
+        .map(row)
+        .collect();
+    assert_eq!(rows, [[1, 2], [2, 0], [0, 0], [0, 1]]);
+
+    let rows: Vec<_> = frames(&grid(2, 1), "{width: 2, wrap: true, gap: 1}")
+        .iter()
+        .map(row)
+        .collect();
+    assert_eq!(rows, [[1, 2], [2, 0], [0, 1]]);
+}
+
+#[test]
+fn test_wrap_tiles_over_view() {
+    let frames = frames(&grid(1, 1), "{width: 5, wrap: true, gap: 1}");
+
+    let rows: Vec<_> = frames.iter().map(row).collect();
+    assert_eq!(rows, [[1, 0, 1, 0, 1], [0, 1, 0, 1, 0]]);
+}
+
+#[test]
+fn test_wrap_empty() {
+    let result = scroll_frames(
+        &Array::new(1),
+        &Layout::default(),
+        &scroll("{width: 2, wrap: true, gap: 0}"),
+        &black(),
+        None,
+    );
+    assert_eq!(
+        result.unwrap_err().to_string(),
+        "wrap requires content or a gap"
+    );
+
+    let frames = frames(&Array::new(1), "{width: 2, wrap: true}");
+    let rows: Vec<_> = frames.iter().map(row).collect();
+    assert_eq!(rows, [[0, 0], [0, 0]]);
+}
+
+#[test]
+fn test_default_range_is_symmetric() {
+    let left: Vec<_> = frames(&grid(3, 1), "{width: 2}").iter().map(row).collect();
+    let right: Vec<_> = frames(&grid(3, 1), "{width: 2, direction: right}")
+        .iter()
+        .map(row)
+        .collect();
+
+    // One blank frame before the content enters and `width` blank frames after it leaves.
+    assert_eq!(left.len(), 7);
+    assert_eq!(right.len(), 7);
+    assert_eq!(&left[4..], [[3, 0], [0, 0], [0, 0]]);
+    assert_eq!(&right[4..], [[0, 1], [0, 0], [0, 0]]);
+}
+
+#[test]
+fn test_custom_range() {
+    let rows = |yaml| -> Vec<Vec<u8>> { frames(&grid(4, 1), yaml).iter().map(row).collect() };
+
+    assert_eq!(
+        rows("{width: 2, start: left, end: right}"),
+        [[1, 2], [2, 3], [3, 4]]
+    );
+    assert_eq!(
+        rows("{width: 2, direction: right, start: right, end: left}"),
+        [[3, 4], [2, 3], [1, 2]]
+    );
+    assert_eq!(rows("{width: 2, start: 3, end: exit}"), [[4, 0], [0, 0]]);
+    assert_eq!(
+        rows("{width: 2, start: -1, end: 1}"),
+        [[0, 1], [1, 2], [2, 3]]
+    );
+    assert_eq!(
+        rows("{width: 2, direction: right, start: enter, end: 2}"),
+        [[0, 0], [4, 0], [3, 4]]
+    );
+}
+
+#[test]
+fn test_range_with_wrap() {
+    let result = scroll_frames(
+        &grid(2, 1),
+        &Layout::default(),
+        &scroll("{width: 2, wrap: true, start: left}"),
+        &black(),
+        None,
+    );
+
+    assert!(result.is_err());
+}
@@ -1,26 +1,78 @@
+#[cfg(test)]
+mod test;
+
 use std::collections::HashMap;
-use std::fs::File;
+use std::env;
+use std::fmt;
 use std::path::Path;
+use std::str::FromStr;
 
+use failure::format_err;
+use serde::de::{Deserialize, DeserializeOwned, Deserializer, Error};
 use serde_derive::Deserialize;
-use serde_yaml::from_reader;
+use serde_yaml::{from_value, Value};
 
+use crate::include;
+use crate::macros;
+use crate::markup::{self, Segment};
 use crate::model::color::{ColorSet, ColorSetBuilder};
-use crate::model::command;
+use crate::model::command::{self, Align, EffectFields, EffectKind, FitFields, FitMode};
+use crate::model::style::{self, StyleSet};
+use crate::model::Fallback;
 use crate::result::Result;
+use crate::template;
+use crate::transition::Effect;
 
 #[derive(Debug, Clone, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct Source {
     meta: Metadata,
     output: Option<String>,
+    #[serde(default)]
     body: Vec<Command>,
+    scenes: Option<Vec<Scene>>,
+    zones: Option<Vec<Zone>>,
 }
 
 impl Source {
-    pub fn load(path: impl AsRef<Path>) -> Result<Source> {
-        let f = File::open(path)?;
-        Ok(from_reader(f)?)
+    /// Loads a source file. `${name}` placeholders are filled from `vars`,
+    /// then from environment variables, then from the `vars` block of the source.
+    pub fn load(path: impl AsRef<Path>, vars: &HashMap<String, String>) -> Result<Source> {
+        let (mut doc, mut locations) = include::load(path)?;
+        macros::expand(&mut doc, &mut locations)?;
+
+        let source_vars = take_vars(&mut doc)?;
+        template::resolve_value(&mut doc, &|name| {
+            vars.get(name)
+                .cloned()
+                .or_else(|| env::var(name).ok())
+                .or_else(|| source_vars.get(name).cloned())
+        })?;
+
+        let mut source: Source = locations.deserialize(doc)?;
+
+        if source.scenes.is_some() && !source.body.is_empty() {
+            return Err(format_err!("body and scenes cannot be used together"));
+        }
+
+        if source.zones.is_some() && (source.scenes.is_some() || !source.body.is_empty()) {
+            return Err(format_err!("zones cannot be used with body or scenes"));
+        }
+
+        if source.meta.markup {
+            let styles = &source.meta.styles;
+            source.body = expand_markup(&source.body, styles)?;
+
+            for scene in source.scenes.iter_mut().flatten() {
+                scene.body = expand_markup(&scene.body, styles)?;
+            }
+
+            for zone in source.zones.iter_mut().flatten() {
+                zone.body = expand_markup(&zone.body, styles)?;
+            }
+        }
+
+        Ok(source)
     }
 
     pub fn metadata(&self) -> Metadata {
@@ -38,15 +90,263 @@ impl Source {
     pub fn body(&self) -> impl Iterator<Item = &Command> {
         self.body.iter()
     }
+
+    pub fn scenes(&self) -> Option<&[Scene]> {
+        self.scenes.as_ref().map(|s| s.as_slice())
+    }
+
+    pub fn zones(&self) -> Option<&[Zone]> {
+        self.zones.as_ref().map(|z| z.as_slice())
+    }
+}
+
+/// A rectangular part of the panel with its own body.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Zone {
+    #[serde(default, deserialize_with = "parsed")]
+    x: usize,
+    #[serde(default, deserialize_with = "parsed")]
+    y: usize,
+    #[serde(deserialize_with = "parsed")]
+    width: usize,
+    #[serde(default, deserialize_with = "parsed_option")]
+    height: Option<usize>,
+    #[serde(default)]
+    mode: Mode,
+    #[serde(default)]
+    align: Align,
+    scroll: Option<Scroll>,
+    body: Vec<Command>,
+}
+
+impl Zone {
+    /// Column and row of the top left corner in the panel.
+    pub fn position(&self) -> (usize, usize) {
+        (self.x, self.y)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Height in rows. Defaults to the height of the body.
+    pub fn height(&self) -> Option<usize> {
+        self.height
+    }
+
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    pub fn align(&self) -> Align {
+        self.align
+    }
+
+    /// Scroll setting of the zone, falling back to `meta.scroll`,
+    /// with the viewport set to the zone.
+    pub fn scroll(&self, meta: &Metadata) -> Option<Scroll> {
+        self.scroll
+            .as_ref()
+            .or_else(|| meta.scroll())
+            .map(|scroll| scroll.with_view(self.width, self.height))
+    }
+
+    pub fn body(&self) -> impl Iterator<Item = &Command> {
+        self.body.iter()
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Scene {
+    #[serde(default)]
+    mode: Mode,
+    #[serde(default, deserialize_with = "parsed_option")]
+    duration: Option<f32>,
+    #[serde(default)]
+    align: Align,
+    #[serde(default, deserialize_with = "parsed_option")]
+    period: Option<f32>,
+    scroll: Option<Scroll>,
+    transition: Option<Transition>,
+    #[serde(default, deserialize_with = "parsed_option")]
+    cps: Option<f32>,
+    #[serde(default, deserialize_with = "parsed")]
+    cursor: bool,
+    #[serde(rename = "cursor-width", default, deserialize_with = "parsed_option")]
+    cursor_width: Option<usize>,
+    body: Vec<Command>,
+}
+
+impl Scene {
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    /// Duration in seconds. Required except for scroll and typewriter scenes.
+    pub fn duration(&self) -> Option<f32> {
+        self.duration
+    }
+
+    pub fn align(&self) -> Align {
+        self.align
+    }
+
+    /// Seconds of one on/off cycle of a blink scene, or of the cursor of a typewriter scene.
+    pub fn period(&self) -> f32 {
+        self.period.unwrap_or(1.0)
+    }
+
+    /// Characters revealed per second by a typewriter scene.
+    pub fn cps(&self) -> f32 {
+        self.cps.unwrap_or(10.0)
+    }
+
+    /// Width of the cursor block of a typewriter scene, if it has one.
+    /// Defaults to half of the panel height.
+    pub fn cursor(&self, height: usize) -> Option<usize> {
+        if self.cursor {
+            Some(self.cursor_width.unwrap_or(height / 2 + height % 2))
+        } else {
+            None
+        }
+    }
+
+    /// Scroll setting of the scene, falling back to `meta.scroll`.
+    pub fn scroll<'a>(&'a self, meta: &'a Metadata) -> Option<&'a Scroll> {
+        self.scroll.as_ref().or_else(|| meta.scroll())
+    }
+
+    /// Transition from the previous scene into this one.
+    pub fn transition(&self) -> Option<&Transition> {
+        self.transition.as_ref()
+    }
+
+    pub fn body(&self) -> impl Iterator<Item = &Command> {
+        self.body.iter()
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Transition {
+    effect: Effect,
+    #[serde(default, deserialize_with = "parsed_option")]
+    frames: Option<usize>,
+    #[serde(default, deserialize_with = "parsed_option")]
+    seconds: Option<f32>,
+    #[serde(default, deserialize_with = "parsed")]
+    seed: u64,
+}
+
+impl Transition {
+    pub fn effect(&self) -> Effect {
+        self.effect
+    }
+
+    pub fn frames(&self, fps: Option<f32>) -> Result<usize> {
+        match (self.frames, self.seconds) {
+            (Some(frames), None) => Ok(frames),
+            (None, Some(seconds)) => {
+                let fps =
+                    fps.ok_or_else(|| format_err!("transition in seconds requires meta.fps"))?;
+                Ok((seconds * fps).round() as usize)
+            }
+            _ => Err(format_err!("transition needs either frames or seconds")),
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Deserialize)]
+pub enum Mode {
+    #[default]
+    #[serde(rename = "static")]
+    Static,
+    #[serde(rename = "scroll")]
+    Scroll,
+    #[serde(rename = "blink")]
+    Blink,
+    #[serde(rename = "typewriter")]
+    Typewriter,
+}
+
+fn take_vars(doc: &mut Value) -> Result<HashMap<String, String>> {
+    let vars = match doc {
+        Value::Mapping(map) => map.remove(&Value::String("vars".to_string())),
+        _ => None,
+    };
+
+    let vars: HashMap<String, Value> = match vars {
+        Some(v) => from_value(v).map_err(|e| format_err!("vars: {}", e))?,
+        None => HashMap::new(),
+    };
+
+    vars.into_iter()
+        .map(|(n, v)| {
+            let v = template::scalar_string(&v).map_err(|e| format_err!("vars.{}: {}", n, e))?;
+            Ok((n, v))
+        })
+        .collect()
+}
+
+/// Accepts any scalar for a string field, as a variable such as `${track}` may be a number.
+fn scalar_string<'de, D: Deserializer<'de>>(d: D) -> std::result::Result<String, D::Error> {
+    template::scalar_string(&Value::deserialize(d)?).map_err(D::Error::custom)
+}
+
+/// Accepts a string for a numeric or boolean field, as a variable such as `${gap}` is substituted as a string.
+fn parsed<'de, D, T>(d: D) -> std::result::Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr + DeserializeOwned,
+    T::Err: fmt::Display,
+{
+    match Value::deserialize(d)? {
+        Value::String(s) => s
+            .trim()
+            .parse()
+            .map_err(|e| D::Error::custom(format!("invalid value '{}': {}", s, e))),
+        v => from_value(v).map_err(D::Error::custom),
+    }
+}
+
+fn parsed_option<'de, D, T>(d: D) -> std::result::Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr + DeserializeOwned,
+    T::Err: fmt::Display,
+{
+    match Value::deserialize(d)? {
+        Value::Null => Ok(None),
+        v => parsed(v).map(Some).map_err(D::Error::custom),
+    }
 }
 
 #[derive(Debug, Clone, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct Metadata {
     colors: HashMap<String, String>,
-    dot: String,
+    dot: Option<String>,
     font: String,
+    #[serde(default, deserialize_with = "parsed_option")]
+    width: Option<usize>,
+    #[serde(default, deserialize_with = "parsed_option")]
+    fps: Option<f32>,
+    #[serde(default, deserialize_with = "parsed_option")]
+    duration: Option<f32>,
     scroll: Option<Scroll>,
+    #[serde(default)]
+    styles: HashMap<String, Style>,
+    #[serde(default, deserialize_with = "parsed")]
+    markup: bool,
+    svg: Option<SvgDot>,
+    export: Option<Export>,
+    wiring: Option<Wiring>,
 }
 
 impl Metadata {
@@ -54,8 +354,9 @@ impl Metadata {
         self.font.as_str()
     }
 
-    pub fn dot(&self) -> &str {
-        self.dot.as_str()
+    /// Dot image file. Not needed for logical output.
+    pub fn dot(&self) -> Option<&str> {
+        self.dot.as_ref().map(|s| s.as_str())
     }
 
     pub fn color_set(&self) -> Result<ColorSet> {
@@ -68,20 +369,509 @@ impl Metadata {
         builder.build()
     }
 
+    pub fn style_set(&self) -> StyleSet {
+        StyleSet::new(
+            self.styles
+                .iter()
+                .map(|(n, s)| (n.to_string(), s.to_style()))
+                .collect(),
+        )
+    }
+
     pub fn scroll(&self) -> Option<&Scroll> {
         self.scroll.as_ref()
     }
+
+    pub fn fps(&self) -> Option<f32> {
+        self.fps
+    }
+
+    /// The metadata with `fps` in place of meta.fps, so frames are generated at that rate.
+    pub fn with_fps(&self, fps: f32) -> Metadata {
+        Metadata {
+            fps: Some(fps),
+            ..self.clone()
+        }
+    }
+
+    /// Seconds of an animation made of zones.
+    pub fn duration(&self) -> Option<f32> {
+        self.duration
+    }
+
+    pub fn panel_width(&self) -> Option<usize> {
+        self.width.or_else(|| self.scroll.as_ref().and_then(Scroll::width))
+    }
+
+    /// Settings of binary frame export.
+    pub fn export(&self) -> Export {
+        self.export.clone().unwrap_or_default()
+    }
+
+    /// Physical order of LEDs for exported data.
+    pub fn wiring(&self) -> Option<&Wiring> {
+        self.wiring.as_ref()
+    }
+
+    /// Dot of SVG output. Defaults to a procedural circle.
+    pub fn svg(&self) -> SvgDot {
+        self.svg.clone().unwrap_or_default()
+    }
+}
+
+/// Layout of exported frame data for LED controllers.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Export {
+    #[serde(default)]
+    pixel: PixelFormat,
+    #[serde(default, rename = "byte-order")]
+    byte_order: ByteOrder,
+    #[serde(default)]
+    scan: Scan,
+    #[serde(default, deserialize_with = "parsed_option")]
+    header: Option<bool>,
+    #[serde(default, deserialize_with = "parsed_option")]
+    threshold: Option<f32>,
+    name: Option<String>,
+}
+
+impl Export {
+    pub fn pixel(&self) -> PixelFormat {
+        self.pixel
+    }
+
+    /// Byte order of 16 bit values, i.e. RGB565 pixels and header fields.
+    pub fn byte_order(&self) -> ByteOrder {
+        self.byte_order
+    }
+
+    /// Order of pixels. Ignored by the 1-bit formats, which have their own.
+    pub fn scan(&self) -> Scan {
+        self.scan
+    }
+
+    /// Whether the data starts with the size, the frame count and the frame delays.
+    /// Without it, held frames are repeated to play at a fixed rate.
+    pub fn header(&self) -> bool {
+        self.header.unwrap_or(true)
+    }
+
+    /// Luma from which a 1-bit pixel is on.
+    pub fn threshold(&self) -> f32 {
+        self.threshold.unwrap_or(0.5)
+    }
+
+    /// Name of the array in C or Rust source output.
+    pub fn name(&self) -> &str {
+        self.name.as_ref().map(|s| s.as_str()).unwrap_or("frames")
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Deserialize)]
+pub enum PixelFormat {
+    #[default]
+    #[serde(rename = "rgb888")]
+    Rgb888,
+    #[serde(rename = "rgb565")]
+    Rgb565,
+    /// Two pixels per byte, the first one in the high nibble.
+    #[serde(rename = "gray4")]
+    Gray4,
+    /// Each row packed into bytes, the leftmost pixel in the most significant bit.
+    #[serde(rename = "mono-rows")]
+    MonoRows,
+    /// Each column packed into bytes of 8 rows, the top pixel in the least significant bit.
+    #[serde(rename = "mono-columns")]
+    MonoColumns,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Deserialize)]
+pub enum ByteOrder {
+    #[default]
+    #[serde(rename = "little")]
+    Little,
+    #[serde(rename = "big")]
+    Big,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Deserialize)]
+pub enum Scan {
+    /// Row by row, left to right.
+    #[default]
+    #[serde(rename = "rows")]
+    Rows,
+    /// Column by column, top to bottom.
+    #[serde(rename = "columns")]
+    Columns,
+    /// Row by row, alternating the direction.
+    #[serde(rename = "serpentine-rows")]
+    SerpentineRows,
+    /// Column by column, alternating the direction.
+    #[serde(rename = "serpentine-columns")]
+    SerpentineColumns,
+}
+
+/// Chained LED panels making up the display.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Wiring {
+    #[serde(rename = "panel-width", deserialize_with = "parsed")]
+    panel_width: usize,
+    #[serde(rename = "panel-height", deserialize_with = "parsed")]
+    panel_height: usize,
+    #[serde(default)]
+    leds: Scan,
+    chain: Vec<PanelPlace>,
+}
+
+impl Wiring {
+    pub fn panel_size(&self) -> (usize, usize) {
+        (self.panel_width, self.panel_height)
+    }
+
+    /// Order of LEDs inside an unrotated panel.
+    pub fn leds(&self) -> Scan {
+        self.leds
+    }
+
+    /// Panels in the order the data goes through them.
+    pub fn chain(&self) -> &[PanelPlace] {
+        &self.chain
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PanelPlace {
+    #[serde(deserialize_with = "parsed")]
+    x: usize,
+    #[serde(deserialize_with = "parsed")]
+    y: usize,
+    #[serde(default, deserialize_with = "parsed")]
+    rotate: u16,
+    #[serde(default, deserialize_with = "parsed")]
+    flip: bool,
+}
+
+impl PanelPlace {
+    /// Column and row of the top left corner of the panel on the display.
+    pub fn position(&self) -> (usize, usize) {
+        (self.x, self.y)
+    }
+
+    /// Clockwise rotation in degrees.
+    pub fn rotate(&self) -> u16 {
+        self.rotate
+    }
+
+    /// Whether the panel is mirrored horizontally before rotating.
+    pub fn flip(&self) -> bool {
+        self.flip
+    }
+}
+
+/// How each cell is drawn in SVG output.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SvgDot {
+    /// An SVG file drawn in each cell, which fills with `currentColor`.
+    template: Option<String>,
+    #[serde(default)]
+    shape: Shape,
+    /// Size of the shape as a fraction of the cell.
+    #[serde(default, deserialize_with = "parsed_option")]
+    size: Option<f32>,
+    /// Size of a cell in SVG user units.
+    #[serde(default, deserialize_with = "parsed_option")]
+    cell: Option<f32>,
+}
+
+impl SvgDot {
+    pub fn template(&self) -> Option<&str> {
+        self.template.as_ref().map(|s| s.as_str())
+    }
+
+    pub fn shape(&self) -> Shape {
+        self.shape
+    }
+
+    pub fn size(&self) -> f32 {
+        self.size.unwrap_or(0.8)
+    }
+
+    pub fn cell(&self) -> f32 {
+        self.cell.unwrap_or(10.0)
+    }
+}
+
+/// A procedural dot shape of SVG output.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Deserialize)]
+pub enum Shape {
+    #[default]
+    #[serde(rename = "circle")]
+    Circle,
+    #[serde(rename = "square")]
+    Square,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Style {
+    #[serde(rename = "f")]
+    foreground: Option<String>,
+    #[serde(rename = "b")]
+    background: Option<String>,
+    font: Option<String>,
+    #[serde(default, deserialize_with = "parsed_option")]
+    spacing: Option<usize>,
+    fit: Option<FitMode>,
+    #[serde(rename = "fit-width", default, deserialize_with = "parsed_option")]
+    fit_width: Option<usize>,
+    #[serde(rename = "min-ratio", default, deserialize_with = "parsed_option")]
+    min_ratio: Option<f32>,
+    effect: Option<EffectKind>,
+    #[serde(rename = "effect-period", default, deserialize_with = "parsed_option")]
+    effect_period: Option<f32>,
+    #[serde(default, deserialize_with = "parsed_option")]
+    duty: Option<f32>,
+}
+
+impl Style {
+    fn to_style(&self) -> style::Style {
+        style::Style {
+            foreground: self.foreground.clone(),
+            background: self.background.clone(),
+            font: self.font.clone(),
+            spacing: self.spacing,
+            fit: FitFields {
+                mode: self.fit,
+                width: self.fit_width,
+                min_ratio: self.min_ratio,
+            },
+            effect: EffectFields {
+                kind: self.effect,
+                period: self.effect_period,
+                duty: self.duty,
+            },
+        }
+    }
 }
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct Scroll {
-    width: usize,
+    #[serde(default, deserialize_with = "parsed_option")]
+    width: Option<usize>,
+    #[serde(default, deserialize_with = "parsed_option")]
+    height: Option<usize>,
+    #[serde(default)]
+    direction: Direction,
+    #[serde(default, deserialize_with = "parsed_option")]
+    speed: Option<f32>,
+    #[serde(default, deserialize_with = "parsed_option")]
+    cps: Option<f32>,
+    #[serde(default)]
+    step: Step,
+    #[serde(default, deserialize_with = "parsed")]
+    wrap: bool,
+    #[serde(default, deserialize_with = "parsed_option")]
+    gap: Option<usize>,
+    #[serde(default, deserialize_with = "parsed_option")]
+    start: Option<Position>,
+    #[serde(default, deserialize_with = "parsed_option")]
+    end: Option<Position>,
 }
 
 impl Scroll {
-    pub fn width(&self) -> usize {
+    /// Width of the viewport. May only be left out for a scroll zone, which sets it.
+    pub fn width(&self) -> Option<usize> {
         self.width
     }
+
+    pub fn height(&self) -> Option<usize> {
+        self.height
+    }
+
+    fn with_view(&self, width: usize, height: Option<usize>) -> Scroll {
+        Scroll {
+            width: Some(width),
+            height: height.or(self.height),
+            ..self.clone()
+        }
+    }
+
+    pub fn direction(&self) -> Direction {
+        self.direction
+    }
+
+    /// Columns (or rows) to advance per frame.
+    /// `cps` is given in columns per second and converted with `fps`.
+    pub fn speed(&self, fps: Option<f32>) -> Result<f32> {
+        let speed = match (self.speed, self.cps) {
+            (Some(_), Some(_)) => return Err(format_err!("scroll: both speed and cps are set")),
+            (Some(speed), None) => speed,
+            (None, Some(cps)) => {
+                let fps = fps.ok_or_else(|| format_err!("scroll: cps requires meta.fps"))?;
+                cps / fps
+            }
+            (None, None) => 1.0,
+        };
+
+        if speed <= 0.0 || !speed.is_finite() {
+            return Err(format_err!("scroll: speed should be positive"));
+        }
+
+        Ok(speed)
+    }
+
+    pub fn step(&self) -> Step {
+        self.step
+    }
+
+    /// Whether the content repeats seamlessly instead of entering and leaving a blank panel.
+    pub fn wrap(&self) -> bool {
+        self.wrap
+    }
+
+    /// Blank columns (or rows) between repeats of a wrapped scroll.
+    pub fn gap(&self) -> Option<usize> {
+        self.gap
+    }
+
+    pub fn has_range(&self) -> bool {
+        self.start.is_some() || self.end.is_some()
+    }
+
+    /// First offset of the viewport into content of `len` columns (or rows).
+    /// Defaults to entering from the edge.
+    pub fn start_offset(&self, len: usize, view: usize, reverse: bool) -> isize {
+        match self.start {
+            Some(p) => p.offset(len, view, reverse),
+            None => Position::Edge(Edge::Enter).offset(len, view, reverse),
+        }
+    }
+
+    /// Last offset of the viewport. Defaults to `view - 1` past the exit in either direction,
+    /// which keeps the trailing blank frames of the former fixed range.
+    pub fn end_offset(&self, len: usize, view: usize, reverse: bool) -> isize {
+        let trail = view.saturating_sub(1) as isize;
+
+        match self.end {
+            Some(p) => p.offset(len, view, reverse),
+            None if reverse => Position::Edge(Edge::Exit).offset(len, view, reverse) - trail,
+            None => Position::Edge(Edge::Exit).offset(len, view, reverse) + trail,
+        }
+    }
+}
+
+/// A scroll position, given as an edge or as an explicit viewport offset.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum Position {
+    Edge(Edge),
+    Offset(isize),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub enum Edge {
+    /// The content is just outside of the panel, before entering it.
+    #[serde(rename = "enter")]
+    Enter,
+    /// The head of the content is flush with the left (or top) edge.
+    #[serde(rename = "left", alias = "top")]
+    Left,
+    /// The tail of the content is flush with the right (or bottom) edge.
+    #[serde(rename = "right", alias = "bottom")]
+    Right,
+    /// The content has just left the panel.
+    #[serde(rename = "exit")]
+    Exit,
+}
+
+impl FromStr for Position {
+    type Err = serde_yaml::Error;
+
+    fn from_str(s: &str) -> std::result::Result<Position, Self::Err> {
+        match s.parse() {
+            Ok(offset) => Ok(Position::Offset(offset)),
+            Err(_) => from_value(Value::String(s.to_string())).map(Position::Edge),
+        }
+    }
+}
+
+impl Position {
+    fn offset(self, len: usize, view: usize, reverse: bool) -> isize {
+        let (len, view) = (len as isize, view as isize);
+
+        match self {
+            Position::Offset(offset) => offset,
+            Position::Edge(Edge::Left) => 0,
+            Position::Edge(Edge::Right) => len - view,
+            Position::Edge(Edge::Enter) if reverse => len,
+            Position::Edge(Edge::Enter) => -view,
+            Position::Edge(Edge::Exit) if reverse => -view,
+            Position::Edge(Edge::Exit) => len,
+        }
+    }
+}
+
+/// How a fractional scroll position is rendered.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Deserialize)]
+pub enum Step {
+    /// Snaps to the whole column, as LED hardware does.
+    #[default]
+    #[serde(rename = "snap")]
+    Snap,
+    /// Blends the brightness of the two nearest columns.
+    #[serde(rename = "blend")]
+    Blend,
+}
+
+/// The direction the content moves to.
+///
+/// The body is built into a single row, so `up` and `down` move that row through the view.
+/// Bodies of several lines are not supported.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Deserialize)]
+pub enum Direction {
+    #[default]
+    #[serde(rename = "left")]
+    Left,
+    #[serde(rename = "right")]
+    Right,
+    #[serde(rename = "up")]
+    Up,
+    #[serde(rename = "down")]
+    Down,
+}
+
+fn expand_markup(body: &[Command], styles: &HashMap<String, Style>) -> Result<Vec<Command>> {
+    let mut expanded = Vec::new();
+
+    for (i, cmd) in body.iter().enumerate() {
+        match cmd {
+            Command::Text(text) => {
+                let segments = markup::parse(&text.content)
+                    .map_err(|e| format_err!("body[{}].text.c: {}", i, e))?;
+
+                if segments.len() > 1 && text.fit.is_some() {
+                    return Err(format_err!(
+                        "body[{}].text: fit cannot be used with markup tags",
+                        i
+                    ));
+                }
+
+                for seg in segments {
+                    expanded.push(
+                        text.with_segment(seg, styles)
+                            .map_err(|e| format_err!("body[{}].text: {}", i, e))?,
+                    );
+                }
+            }
+            cmd => expanded.push(cmd.clone()),
+        }
+    }
+
+    Ok(expanded)
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -90,9 +880,11 @@ pub enum Command {
     Text(Text),
     #[serde(rename = "space")]
     Space(Space),
+    #[serde(rename = "pause")]
+    Pause(Pause),
 }
 
-impl<'a> command::Command<'a, Text, Space> for Command {
+impl<'a> command::Command<'a, Text, Space, Pause> for Command {
     fn when_text<U>(&'a self, none: U, f: impl FnOnce(&Text) -> U) -> U {
         if let Command::Text(text) = self {
             f(text)
@@ -108,17 +900,104 @@ impl<'a> command::Command<'a, Text, Space> for Command {
             none
         }
     }
+
+    fn when_pause<U>(&'a self, none: U, f: impl FnOnce(&Pause) -> U) -> U {
+        if let Command::Pause(pause) = self {
+            f(pause)
+        } else {
+            none
+        }
+    }
 }
 
 #[derive(Debug, Clone, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct Text {
-    #[serde(rename = "c")]
+    #[serde(rename = "c", deserialize_with = "scalar_string")]
     content: String,
     #[serde(rename = "f")]
     foreground: Option<String>,
     #[serde(rename = "b")]
     background: Option<String>,
+    #[serde(rename = "s")]
+    style: Option<String>,
+    font: Option<String>,
+    #[serde(default, deserialize_with = "parsed_option")]
+    spacing: Option<usize>,
+    fit: Option<FitMode>,
+    #[serde(rename = "fit-width", default, deserialize_with = "parsed_option")]
+    fit_width: Option<usize>,
+    #[serde(rename = "min-ratio", default, deserialize_with = "parsed_option")]
+    min_ratio: Option<f32>,
+    effect: Option<EffectKind>,
+    #[serde(rename = "effect-period", default, deserialize_with = "parsed_option")]
+    effect_period: Option<f32>,
+    #[serde(default, deserialize_with = "parsed_option")]
+    duty: Option<f32>,
+}
+
+impl Text {
+    /// Makes a command of a markup segment.
+    ///
+    /// Each tag takes precedence over the tags and the text enclosing it, and the style of a tag
+    /// or of the text over what encloses them. Styles are resolved here, so the command has none.
+    fn with_segment(&self, segment: Segment, styles: &HashMap<String, Style>) -> Result<Command> {
+        let (layers, content) = match &segment {
+            Segment::Text(content, layers) => (layers, Some(content)),
+            Segment::Space(_, layers) => (layers, None),
+        };
+
+        if layers.is_empty() {
+            if let Some(content) = content {
+                return Ok(Command::Text(Text {
+                    content: content.to_string(),
+                    ..self.clone()
+                }));
+            }
+        }
+
+        let lookup = |name: &Option<String>| match name {
+            Some(name) => styles
+                .get(name)
+                .map(Style::to_style)
+                .ok_or_else(|| format_err!("unknown style: '{}'", name)),
+            None => Ok(style::Style::default()),
+        };
+
+        // From the outermost, each layer falls back to what encloses it.
+        let mut resolved = lookup(&self.style)?.under(self);
+        for attrs in layers {
+            let tag = style::Style {
+                foreground: attrs.foreground.clone(),
+                background: attrs.background.clone(),
+                font: attrs.font.clone(),
+                ..style::Style::default()
+            };
+            resolved = tag.or(&lookup(&attrs.style)?.or(&resolved));
+        }
+
+        Ok(match segment {
+            Segment::Text(content, _) => Command::Text(Text {
+                content,
+                foreground: resolved.foreground,
+                background: resolved.background,
+                style: None,
+                font: resolved.font,
+                spacing: resolved.spacing,
+                fit: resolved.fit.mode,
+                fit_width: resolved.fit.width,
+                min_ratio: resolved.fit.min_ratio,
+                effect: resolved.effect.kind,
+                effect_period: resolved.effect.period,
+                duty: resolved.effect.duty,
+            }),
+            Segment::Space(width, _) => Command::Space(Space {
+                width,
+                background: resolved.background,
+                style: None,
+            }),
+        })
+    }
 }
 
 impl command::Text for Text {
@@ -133,15 +1012,45 @@ impl command::Text for Text {
     fn background(&self) -> Option<&str> {
         self.background.as_ref().map(|s| &**s)
     }
+
+    fn fit(&self) -> FitFields {
+        FitFields {
+            mode: self.fit,
+            width: self.fit_width,
+            min_ratio: self.min_ratio,
+        }
+    }
+
+    fn style(&self) -> Option<&str> {
+        self.style.as_ref().map(|s| &**s)
+    }
+
+    fn font(&self) -> Option<&str> {
+        self.font.as_ref().map(|s| &**s)
+    }
+
+    fn spacing(&self) -> Option<usize> {
+        self.spacing
+    }
+
+    fn effect(&self) -> EffectFields {
+        EffectFields {
+            kind: self.effect,
+            period: self.effect_period,
+            duty: self.duty,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct Space {
-    #[serde(rename = "w")]
+    #[serde(rename = "w", deserialize_with = "parsed")]
     width: usize,
     #[serde(rename = "b")]
     background: Option<String>,
+    #[serde(rename = "s")]
+    style: Option<String>,
 }
 
 impl command::Space for Space {
@@ -152,4 +1061,33 @@ impl command::Space for Space {
     fn background(&self) -> Option<&str> {
         self.background.as_ref().map(|s| &**s)
     }
+
+    fn style(&self) -> Option<&str> {
+        self.style.as_ref().map(|s| &**s)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Pause {
+    #[serde(default, deserialize_with = "parsed_option")]
+    frames: Option<usize>,
+    #[serde(default, deserialize_with = "parsed_option")]
+    seconds: Option<f32>,
+    #[serde(default)]
+    align: Align,
+}
+
+impl command::Pause for Pause {
+    fn frames(&self) -> Option<usize> {
+        self.frames
+    }
+
+    fn seconds(&self) -> Option<f32> {
+        self.seconds
+    }
+
+    fn align(&self) -> Align {
+        self.align
+    }
 }
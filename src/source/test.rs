@@ -0,0 +1,130 @@
+use super::*;
+use crate::model::command::Text as _;
+
+fn styles() -> HashMap<String, Style> {
+    serde_yaml::from_str(
+        "
+warning: {f: red, b: black}
+big: {font: big, spacing: 2}
+",
+    )
+    .unwrap()
+}
+
+fn texts(body: &[Command]) -> Vec<&Text> {
+    body.iter()
+        .filter_map(|cmd| match cmd {
+            Command::Text(text) => Some(text),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn test_markup_over_outer_text() {
+    let body: Vec<Command> = serde_yaml::from_str(
+        "
+- text: {c: 'A{s=warning}B{/}{s=warning,f=blue}C{/}', f: green, s: big}
+",
+    )
+    .unwrap();
+
+    let expanded = expand_markup(&body, &styles()).unwrap();
+    let texts = texts(&expanded);
+
+    // Without a tag the text keeps its own fields and style.
+    assert_eq!(texts[0].foreground(), Some("green"));
+    assert_eq!(texts[0].style(), Some("big"));
+
+    // The style of a tag wins over the text, which wins over its own style.
+    assert_eq!(texts[1].foreground(), Some("red"));
+    assert_eq!(texts[1].background(), Some("black"));
+    assert_eq!(texts[1].font(), Some("big"));
+    assert_eq!(texts[1].spacing(), Some(2));
+
+    // Attributes of a tag win over its style.
+    assert_eq!(texts[2].foreground(), Some("blue"));
+    assert_eq!(texts[2].background(), Some("black"));
+}
+
+#[test]
+fn test_markup_nested_tags() {
+    let body: Vec<Command> =
+        serde_yaml::from_str("- text: {c: '{f=blue}{s=warning}A{/}B{/}'}").unwrap();
+
+    let expanded = expand_markup(&body, &styles()).unwrap();
+    let texts = texts(&expanded);
+
+    assert_eq!(texts[0].foreground(), Some("red"));
+    assert_eq!(texts[1].foreground(), Some("blue"));
+
+    let body: Vec<Command> = serde_yaml::from_str("- text: {c: '{s=none}A{/}'}").unwrap();
+    assert!(expand_markup(&body, &styles()).is_err());
+}
+
+#[test]
+fn test_numeric_fields_parse_strings() {
+    let body: Vec<Command> =
+        serde_yaml::from_str("- text: {c: '0930', spacing: '2'}\n- space: {w: ' 3'}").unwrap();
+
+    let texts = texts(&body);
+    assert_eq!(texts[0].content(), "0930");
+    assert_eq!(texts[0].spacing(), Some(2));
+    match &body[1] {
+        Command::Space(space) => assert_eq!(command::Space::width(space), 3),
+        _ => panic!("not a space"),
+    }
+
+    let scroll: Scroll = serde_yaml::from_str("{width: '8', start: '-2', end: exit}").unwrap();
+    assert_eq!(scroll.width(), Some(8));
+    assert_eq!(scroll.start, Some(Position::Offset(-2)));
+    assert_eq!(scroll.end, Some(Position::Edge(Edge::Exit)));
+
+    let err = serde_yaml::from_str::<Vec<Command>>("- space: {w: wide}").unwrap_err();
+    assert!(err.to_string().contains("invalid value 'wide'"), "{}", err);
+}
+
+/// Loads `yaml` as a source file.
+fn load(name: &str, yaml: &str) -> Result<Source> {
+    let path = env::temp_dir().join(format!(
+        "led-image-source-{}-{}.yaml",
+        std::process::id(),
+        name
+    ));
+    std::fs::write(&path, yaml).unwrap();
+    let source = Source::load(&path, &HashMap::new());
+    std::fs::remove_file(path).unwrap();
+    source
+}
+
+#[test]
+fn test_plain_scalars_stay_strings() {
+    let source = load(
+        "scalars",
+        "meta: {colors: {}, font: test}\noutput: 2024\nbody:\n  - text: {c: 007}\n  - text: {c: 1.10}\n",
+    )
+    .unwrap();
+
+    assert_eq!(source.output(), Some("2024".to_string()));
+    let body: Vec<_> = source.body().cloned().collect();
+    let texts = texts(&body);
+    assert_eq!(texts[0].content(), "007");
+    assert_eq!(texts[1].content(), "1.10");
+}
+
+#[test]
+fn test_error_location() {
+    let err = load(
+        "location",
+        "meta: {colors: {}, font: test}\nbody:\n  - space: {w: 1}\n  - text: {c: A, size: 2}\n",
+    )
+    .unwrap_err()
+    .to_string();
+
+    assert!(
+        err.starts_with("body[1].text: unknown field `size`"),
+        "{}",
+        err
+    );
+    assert!(err.ends_with("at line 4 column 11"), "{}", err);
+}
@@ -0,0 +1,155 @@
+#[cfg(test)]
+mod test;
+
+use std::collections::BTreeSet;
+
+use failure::format_err;
+use serde_yaml::Value;
+
+use crate::result::Result;
+
+/// Replaces `${name}` placeholders in every string of `value` for which `lookup` returns a value.
+///
+/// Other placeholders and `$${` escapes are left as they are.
+/// Substituted values stay strings; numeric fields of the source parse them.
+pub fn substitute_value(value: &mut Value, lookup: &dyn Fn(&str) -> Option<String>) {
+    walk(value, &mut |s| {
+        let parts = parts(s);
+
+        Some(Value::String(substitute_parts(&parts, lookup)))
+    });
+}
+
+/// Replaces all placeholders in `value`, falling back to `${name:-default}` defaults,
+/// and turns `$${` escapes into `${`.
+///
+/// Fails with the list of all unset variables if any placeholder is left.
+pub fn resolve_value(value: &mut Value, lookup: &dyn Fn(&str) -> Option<String>) -> Result<()> {
+    let mut missing = BTreeSet::new();
+
+    walk(value, &mut |s| {
+        let parts = parts(s);
+
+        let mut result = String::new();
+        for part in parts {
+            match part {
+                Part::Text(t) => result.push_str(t),
+                Part::Escape => result.push_str("${"),
+                Part::Placeholder { name, default, raw } => {
+                    match lookup(name).or_else(|| default.map(|d| d.to_string())) {
+                        Some(v) => result.push_str(&v),
+                        None => {
+                            missing.insert(name.to_string());
+                            result.push_str(raw);
+                        }
+                    }
+                }
+            }
+        }
+        Some(Value::String(result))
+    });
+
+    if missing.is_empty() {
+        Ok(())
+    } else {
+        Err(format_err!(
+            "unset variables: {}",
+            missing.into_iter().collect::<Vec<_>>().join(", ")
+        ))
+    }
+}
+
+/// Formats a scalar value as a string to be substituted.
+pub fn scalar_string(value: &Value) -> Result<String> {
+    match value {
+        Value::String(s) => Ok(s.to_string()),
+        Value::Number(n) => Ok(n.to_string()),
+        Value::Bool(b) => Ok(b.to_string()),
+        Value::Null => Ok(String::new()),
+        _ => Err(format_err!("expected a scalar value")),
+    }
+}
+
+fn walk(value: &mut Value, f: &mut dyn FnMut(&str) -> Option<Value>) {
+    match value {
+        Value::String(s) => {
+            if let Some(v) = f(s) {
+                *value = v;
+            }
+        }
+        Value::Sequence(seq) => {
+            for v in seq.iter_mut() {
+                walk(v, f);
+            }
+        }
+        Value::Mapping(map) => {
+            for (_, v) in map.iter_mut() {
+                walk(v, f);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Part<'a> {
+    Text(&'a str),
+    Escape,
+    Placeholder {
+        name: &'a str,
+        default: Option<&'a str>,
+        raw: &'a str,
+    },
+}
+
+fn parts(s: &str) -> Vec<Part<'_>> {
+    let mut parts = Vec::new();
+    let mut rest = s;
+
+    while let Some(pos) = rest.find('$') {
+        if pos > 0 {
+            parts.push(Part::Text(&rest[..pos]));
+        }
+        rest = &rest[pos..];
+
+        if rest.starts_with("$${") {
+            parts.push(Part::Escape);
+            rest = &rest[3..];
+        } else if let (true, Some(end)) = (rest.starts_with("${"), rest.find('}')) {
+            let inner = &rest[2..end];
+            let mut split = inner.splitn(2, ":-");
+            parts.push(Part::Placeholder {
+                name: split.next().unwrap(),
+                default: split.next(),
+                raw: &rest[..=end],
+            });
+            rest = &rest[end + 1..];
+        } else {
+            parts.push(Part::Text("$"));
+            rest = &rest[1..];
+        }
+    }
+
+    if !rest.is_empty() {
+        parts.push(Part::Text(rest));
+    }
+
+    parts
+}
+
+fn substitute_parts(parts: &[Part], lookup: &dyn Fn(&str) -> Option<String>) -> String {
+    let mut result = String::new();
+
+    for part in parts {
+        match *part {
+            Part::Text(t) => result.push_str(t),
+            Part::Escape => result.push_str("$${"),
+            Part::Placeholder { name, raw, .. } => match lookup(name) {
+                Some(v) => result.push_str(&v),
+                None => result.push_str(raw),
+            },
+        }
+    }
+
+    result
+}
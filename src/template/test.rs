@@ -0,0 +1,65 @@
+use super::*;
+
+fn lookup(name: &str) -> Option<String> {
+    match name {
+        "station" => Some("Shinjuku".to_string()),
+        "gap" => Some("3".to_string()),
+        "time" => Some("0930".to_string()),
+        "price" => Some("1.50".to_string()),
+        "red" => Some("#ff0000".to_string()),
+        "out" => Some("board.png".to_string()),
+        _ => None,
+    }
+}
+
+fn string(s: &str) -> Value {
+    Value::String(s.to_string())
+}
+
+#[test]
+fn test_substitute_value() {
+    let samples = vec![
+        ("Next: ${station}", "Next: Shinjuku"),
+        ("${station}/${station}", "Shinjuku/Shinjuku"),
+        ("${unknown} ${station}", "${unknown} Shinjuku"),
+        ("$${station} $5 ${", "$${station} $5 ${"),
+    ];
+
+    for (s, to_be) in samples {
+        let mut value = string(s);
+        substitute_value(&mut value, &lookup);
+        assert_eq!(value, string(to_be), "substituting '{}'", s);
+    }
+}
+
+#[test]
+fn test_substitute_value_keeps_strings() {
+    let mut value: Value = serde_yaml::from_str(
+        "{w: '${gap}', t: '${time}', p: '${price}', f: '${red}', output: '${out}'}",
+    )
+    .unwrap();
+    substitute_value(&mut value, &lookup);
+
+    let to_be: Value =
+        serde_yaml::from_str("{w: '3', t: '0930', p: '1.50', f: '#ff0000', output: board.png}")
+            .unwrap();
+    assert_eq!(value, to_be);
+}
+
+#[test]
+fn test_resolve_value() {
+    let mut value: Value =
+        serde_yaml::from_str("['Next ${station}', '${track:-1}', '$${station}']").unwrap();
+    resolve_value(&mut value, &lookup).unwrap();
+
+    let to_be: Value = serde_yaml::from_str("[Next Shinjuku, '1', '${station}']").unwrap();
+    assert_eq!(value, to_be);
+}
+
+#[test]
+fn test_resolve_value_lists_unset() {
+    let mut value: Value = serde_yaml::from_str("['${b} ${a}', '${b}', '${c:-x}']").unwrap();
+    let err = resolve_value(&mut value, &lookup).unwrap_err();
+
+    assert_eq!(err.to_string(), "unset variables: a, b");
+}
@@ -0,0 +1,152 @@
+#[cfg(test)]
+mod test;
+
+use failure::format_err;
+
+use crate::effect;
+use crate::frame::{frame_count, Frame};
+use crate::model::array::Array;
+use crate::model::color::Color;
+use crate::model::command::Align;
+use crate::render::Renderer;
+use crate::result::Result;
+use crate::scroll::scroll_frames;
+use crate::source::{Metadata, Mode, Scene};
+use crate::transition::transition_frames;
+use crate::typewriter::typewriter_frames;
+
+/// Renders scenes one after another into a single frame sequence.
+pub fn timeline_frames(
+    scenes: &[Scene],
+    meta: &Metadata,
+    renderer: &Renderer,
+) -> Result<Vec<Frame>> {
+    if scenes.is_empty() {
+        return Err(format_err!("scenes must not be empty"));
+    }
+
+    let mut frames: Vec<Frame> = Vec::new();
+
+    for (i, scene) in scenes.iter().enumerate() {
+        let scene_frames =
+            scene_frames(scene, meta, renderer).map_err(|e| format_err!("scenes[{}]: {}", i, e))?;
+
+        if let (Some(first), Some(last)) = (scene_frames.first(), frames.last()) {
+            if first.array.width() != last.array.width()
+                || first.array.height() != last.array.height()
+            {
+                return Err(format_err!(
+                    "scenes[{}]: frame size {}x{} differs from the previous scene {}x{}",
+                    i,
+                    first.array.width(),
+                    first.array.height(),
+                    last.array.width(),
+                    last.array.height()
+                ));
+            }
+        }
+
+        if let (Some(transition), Some(first), Some(last)) =
+            (scene.transition(), scene_frames.first(), frames.last())
+        {
+            let between = transition_frames(
+                transition.effect(),
+                &last.array,
+                &first.array,
+                transition.frames(renderer.fps())?,
+                transition.seed(),
+            )?;
+            frames.extend(between.into_iter().map(|array| Frame { array, hold: 1 }));
+        }
+
+        frames.extend(scene_frames);
+    }
+
+    Ok(frames)
+}
+
+fn scene_frames(scene: &Scene, meta: &Metadata, renderer: &Renderer) -> Result<Vec<Frame>> {
+    let (array, layout) = renderer.build(scene.body())?;
+    let background = renderer.background();
+
+    if scene.mode() == Mode::Scroll {
+        let scroll = scene
+            .scroll(meta)
+            .ok_or_else(|| format_err!("scroll scene requires scroll setting"))?;
+        return scroll_frames(&array, &layout, scroll, background, renderer.fps());
+    }
+
+    let width = renderer
+        .panel_width()
+        .ok_or_else(|| format_err!("scenes require meta.width or meta.scroll"))?;
+    let fps = renderer
+        .fps()
+        .ok_or_else(|| format_err!("scenes require meta.fps"))?;
+    let panel = place(&array, width, scene.align(), background);
+    let panel_at = |frame: usize| {
+        let array = effect::apply(&array, &layout.effects, frame as f32 / fps);
+        place(&array, width, scene.align(), background)
+    };
+
+    if scene.mode() == Mode::Typewriter {
+        return typewriter_frames(&array, &layout, scene, renderer, width, fps);
+    }
+
+    let duration = scene
+        .duration()
+        .ok_or_else(|| format_err!("{:?} scene requires duration", scene.mode()))?;
+    let total = frame_count(duration, fps)?;
+
+    match scene.mode() {
+        Mode::Static if layout.effects.is_empty() => Ok(vec![Frame {
+            array: panel,
+            hold: total,
+        }]),
+        Mode::Static => Ok((0..total)
+            .map(|i| Frame {
+                array: panel_at(i),
+                hold: 1,
+            })
+            .collect()),
+        Mode::Blink => {
+            let blank = array.slice(array.width() as isize, width, background);
+            let half = ((scene.period() * fps / 2.0).round() as usize).max(1);
+
+            let mut frames = Vec::new();
+            let mut elapsed = 0;
+            while elapsed < total {
+                let on = (elapsed / half) & 1 == 0;
+                let hold = half.min(total - elapsed);
+
+                if on && !layout.effects.is_empty() {
+                    frames.extend((elapsed..elapsed + hold).map(|i| Frame {
+                        array: panel_at(i),
+                        hold: 1,
+                    }));
+                } else {
+                    frames.push(Frame {
+                        array: if on { panel.clone() } else { blank.clone() },
+                        hold,
+                    });
+                }
+                elapsed += hold;
+            }
+
+            Ok(frames)
+        }
+        Mode::Scroll | Mode::Typewriter => unreachable!(),
+    }
+}
+
+/// Places `array` in a panel of `width` columns.
+pub fn place(array: &Array, width: usize, align: Align, background: &Color) -> Array {
+    let spare = width as isize - array.width() as isize;
+
+    let offset = match align {
+        Align::Left => 0,
+        Align::Center => -spare / 2,
+        Align::Right => -spare,
+    };
+
+    array.slice(offset, width, background)
+}
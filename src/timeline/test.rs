@@ -0,0 +1,115 @@
+use super::*;
+
+fn meta() -> Metadata {
+    serde_yaml::from_str(
+        "
+colors: {_background: '#000000', _foreground: '#ffffff'}
+font: test
+width: 4
+fps: 10
+",
+    )
+    .unwrap()
+}
+
+fn frames(scenes: &str) -> Result<Vec<Frame>> {
+    let meta = meta();
+    let scenes: Vec<Scene> = serde_yaml::from_str(scenes).unwrap();
+    timeline_frames(&scenes, &meta, &Renderer::with_test_font(&meta))
+}
+
+fn lit(frame: &Frame) -> usize {
+    frame
+        .array
+        .iter()
+        .filter(|(_, _, c)| c.r::<u8>() > 0)
+        .count()
+}
+
+#[test]
+fn test_scenes_concatenate() {
+    let frames = frames(
+        "
+- {duration: 0.5, body: [text: {c: ab}]}
+- {mode: blink, duration: 1, period: 0.4, body: [text: {c: abc}]}
+",
+    )
+    .unwrap();
+
+    let holds: Vec<_> = frames.iter().map(|f| f.hold).collect();
+    assert_eq!(holds, [5, 2, 2, 2, 2, 2]);
+    assert!(frames.iter().all(|f| f.array.width() == 4));
+
+    let lit: Vec<_> = frames.iter().map(lit).collect();
+    assert_eq!(lit, [2, 3, 0, 3, 0, 3]);
+}
+
+#[test]
+fn test_blink_last_half_is_cut() {
+    let frames =
+        frames("- {mode: blink, duration: 0.5, period: 0.4, body: [text: {c: a}]}").unwrap();
+
+    let holds: Vec<_> = frames.iter().map(|f| f.hold).collect();
+    assert_eq!(holds, [2, 2, 1]);
+}
+
+#[test]
+fn test_transition_between_scenes() {
+    let frames = frames(
+        "
+- {duration: 0.2, body: [text: {c: a}]}
+- duration: 0.3
+  transition: {effect: wipe-left, frames: 3}
+  body: [text: {c: ab}]
+",
+    )
+    .unwrap();
+
+    let holds: Vec<_> = frames.iter().map(|f| f.hold).collect();
+    assert_eq!(holds, [2, 1, 1, 1, 3]);
+}
+
+#[test]
+fn test_scene_size_mismatch() {
+    let err = frames(
+        "
+- {duration: 0.2, body: [text: {c: a}]}
+- {mode: scroll, scroll: {width: 3}, body: [text: {c: a}]}
+",
+    )
+    .unwrap_err();
+
+    assert_eq!(
+        err.to_string(),
+        "scenes[1]: frame size 3x1 differs from the previous scene 4x1"
+    );
+}
+
+#[test]
+fn test_empty_scenes() {
+    assert_eq!(
+        frames("[]").unwrap_err().to_string(),
+        "scenes must not be empty"
+    );
+}
+
+#[test]
+fn test_non_positive_duration() {
+    let err = frames("- {duration: 0, body: [text: {c: a}]}").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "scenes[0]: duration must be positive, got 0"
+    );
+
+    let err = frames("- {mode: blink, duration: -1, body: [text: {c: a}]}").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "scenes[0]: duration must be positive, got -1"
+    );
+
+    let err = frames("- {duration: 0.01, body: [text: {c: a}]}").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "scenes[0]: duration 0.01 is shorter than a frame at 10 fps"
+    );
+}
@@ -0,0 +1,151 @@
+#[cfg(test)]
+mod test;
+
+use failure::format_err;
+use serde_derive::Deserialize;
+
+use crate::model::array::Array;
+use crate::result::Result;
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub enum Effect {
+    #[serde(rename = "cut")]
+    Cut,
+    #[serde(rename = "wipe-left")]
+    WipeLeft,
+    #[serde(rename = "wipe-right")]
+    WipeRight,
+    #[serde(rename = "wipe-up")]
+    WipeUp,
+    #[serde(rename = "wipe-down")]
+    WipeDown,
+    #[serde(rename = "curtain-open")]
+    CurtainOpen,
+    #[serde(rename = "dissolve")]
+    Dissolve,
+    /// The next content slides in from the right, covering the current one.
+    #[serde(rename = "slide-in")]
+    SlideIn,
+    /// The current content slides out to the left, uncovering the next one.
+    #[serde(rename = "slide-out")]
+    SlideOut,
+}
+
+/// Generates `frames` intermediate frames going from `from` to `to`.
+/// The result contains neither `from` nor `to` itself. `seed` fixes the dissolve pattern.
+pub fn transition_frames(
+    effect: Effect,
+    from: &Array,
+    to: &Array,
+    frames: usize,
+    seed: u64,
+) -> Result<Vec<Array>> {
+    let (w, h) = (from.width(), from.height());
+    if (w, h) != (to.width(), to.height()) {
+        return Err(format_err!(
+            "transition needs arrays of the same size, but {}x{} and {}x{}",
+            w,
+            h,
+            to.width(),
+            to.height()
+        ));
+    }
+
+    if effect == Effect::Cut {
+        return Ok(Vec::new());
+    }
+
+    let order = if effect == Effect::Dissolve {
+        shuffled(w * h, seed)
+    } else {
+        Vec::new()
+    };
+
+    let result = (1..=frames)
+        .map(|i| {
+            let p = i as f32 / (frames + 1) as f32;
+            let cut_x = (p * w as f32).round() as usize;
+            let cut_y = (p * h as f32).round() as usize;
+
+            compose(w, h, |x, y| match effect {
+                Effect::Cut => Cell::From(x, y),
+                Effect::WipeLeft => pick(x >= w - cut_x, x, y),
+                Effect::WipeRight => pick(x < cut_x, x, y),
+                Effect::WipeUp => pick(y >= h - cut_y, x, y),
+                Effect::WipeDown => pick(y < cut_y, x, y),
+                Effect::CurtainOpen => {
+                    let distance = (2 * x + 1) as isize - w as isize;
+                    pick(distance.unsigned_abs() <= cut_x, x, y)
+                }
+                Effect::Dissolve => pick(order[y * w + x] < (p * (w * h) as f32) as usize, x, y),
+                Effect::SlideIn => {
+                    if x >= w - cut_x {
+                        Cell::To(x + cut_x - w, y)
+                    } else {
+                        Cell::From(x, y)
+                    }
+                }
+                Effect::SlideOut => {
+                    if x + cut_x < w {
+                        Cell::From(x + cut_x, y)
+                    } else {
+                        Cell::To(x, y)
+                    }
+                }
+            })
+        })
+        .map(|cells| {
+            let mut array = Array::with_dim(w, h);
+            for line in cells {
+                array.add_line(
+                    line.into_iter()
+                        .map(|cell| match cell {
+                            Cell::From(x, y) => from.get_line(x).unwrap()[y].clone(),
+                            Cell::To(x, y) => to.get_line(x).unwrap()[y].clone(),
+                        })
+                        .collect(),
+                );
+            }
+            array
+        })
+        .collect();
+
+    Ok(result)
+}
+
+enum Cell {
+    From(usize, usize),
+    To(usize, usize),
+}
+
+fn pick(to: bool, x: usize, y: usize) -> Cell {
+    if to {
+        Cell::To(x, y)
+    } else {
+        Cell::From(x, y)
+    }
+}
+
+fn compose(w: usize, h: usize, f: impl Fn(usize, usize) -> Cell) -> Vec<Vec<Cell>> {
+    (0..w).map(|x| (0..h).map(|y| f(x, y)).collect()).collect()
+}
+
+/// Rank of each of `n` cells in a random order determined by `seed`.
+fn shuffled(n: usize, seed: u64) -> Vec<usize> {
+    let mut state = seed ^ 0x9e37_79b9_7f4a_7c15;
+    let mut next = move || {
+        // xorshift64*
+        state ^= state >> 12;
+        state ^= state << 25;
+        state ^= state >> 27;
+        state.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    };
+
+    let mut order: Vec<usize> = (0..n).collect();
+    for i in (1..n).rev() {
+        let j = (next() % (i as u64 + 1)) as usize;
+        order.swap(i, j);
+    }
+
+    order
+}
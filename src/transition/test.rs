@@ -0,0 +1,80 @@
+use super::*;
+use crate::model::color::Color;
+
+fn count_to(array: &Array, to: &Color) -> usize {
+    array.iter().filter(|(_, _, c)| *c == to).count()
+}
+
+#[test]
+fn test_wipe_left() {
+    let black = Color::new(0u8, 0u8, 0u8, 255u8);
+    let white = Color::new(255u8, 255u8, 255u8, 255u8);
+    let from = Array::filled(4, 2, &black);
+    let to = Array::filled(4, 2, &white);
+
+    let frames = transition_frames(Effect::WipeLeft, &from, &to, 3, 0).unwrap();
+    assert_eq!(frames.len(), 3);
+
+    for (i, frame) in frames.iter().enumerate() {
+        let columns = i + 1;
+        for (x, _, c) in frame.iter() {
+            let expected = if x >= 4 - columns { &white } else { &black };
+            assert_eq!(c, expected, "frame {} x {}", i, x);
+        }
+    }
+}
+
+#[test]
+fn test_cut_has_no_frames() {
+    let c = Color::new(0u8, 0u8, 0u8, 255u8);
+    let a = Array::filled(3, 3, &c);
+
+    assert!(transition_frames(Effect::Cut, &a, &a, 10, 0)
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn test_dissolve_is_deterministic_and_monotonic() {
+    let black = Color::new(0u8, 0u8, 0u8, 255u8);
+    let white = Color::new(255u8, 255u8, 255u8, 255u8);
+    let from = Array::filled(8, 8, &black);
+    let to = Array::filled(8, 8, &white);
+
+    let a = transition_frames(Effect::Dissolve, &from, &to, 7, 42).unwrap();
+    let b = transition_frames(Effect::Dissolve, &from, &to, 7, 42).unwrap();
+    let c = transition_frames(Effect::Dissolve, &from, &to, 7, 43).unwrap();
+
+    let cells = |frames: &[Array]| -> Vec<Vec<Color>> {
+        frames
+            .iter()
+            .map(|f| f.iter().map(|(_, _, c)| c.clone()).collect())
+            .collect()
+    };
+    assert_eq!(cells(&a), cells(&b));
+    assert_ne!(cells(&a), cells(&c));
+
+    for i in 0..a.len() {
+        assert_eq!(count_to(&a[i], &white), 64 * (i + 1) / 8);
+
+        if i > 0 {
+            for ((_, _, prev), (_, _, cur)) in a[i - 1].iter().zip(a[i].iter()) {
+                assert!(prev != &white || cur == &white);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_size_mismatch() {
+    let c = Color::new(0u8, 0u8, 0u8, 255u8);
+
+    assert!(transition_frames(
+        Effect::WipeUp,
+        &Array::filled(3, 3, &c),
+        &Array::filled(4, 3, &c),
+        2,
+        0
+    )
+    .is_err());
+}
@@ -0,0 +1,89 @@
+#[cfg(test)]
+mod test;
+
+use crate::builder::Layout;
+use crate::effect;
+use crate::frame::Frame;
+use crate::model::array::Array;
+use crate::render::Renderer;
+use crate::result::Result;
+use crate::source::Scene;
+use crate::timeline::place;
+
+/// Reveals `array` one character at a time on a panel of `width` columns.
+///
+/// Characters are the glyph spans of `layout`. Once all of them are shown, the frame stays
+/// until the duration of the scene ends, with the cursor blinking if the scene has one.
+/// Content wider than the panel is shifted to keep the cursor in view.
+pub fn typewriter_frames(
+    array: &Array,
+    layout: &Layout,
+    scene: &Scene,
+    renderer: &Renderer,
+    width: usize,
+    fps: f32,
+) -> Result<Vec<Frame>> {
+    let background = renderer.background();
+    let cursor = scene.cursor(array.height());
+    let foreground = match cursor {
+        Some(_) => Some(renderer.foreground()?.clone()),
+        None => None,
+    };
+
+    let count = layout.glyphs.len();
+    let typing = (count as f32 * fps / scene.cps()).ceil() as usize;
+    let total = scene
+        .duration()
+        .map(|d| (d * fps).round() as usize)
+        .unwrap_or(0)
+        .max(typing + 1);
+    let half = ((scene.period() * fps / 2.0).round() as usize).max(1);
+
+    let render = |frame: usize, shown: usize, cursor_on: bool| {
+        let hidden = layout
+            .glyphs
+            .get(shown)
+            .map(|g| g.start)
+            .unwrap_or_else(|| array.width());
+        let cursor_width = cursor.unwrap_or(0);
+
+        let canvas = effect::apply(array, &layout.effects, frame as f32 / fps)
+            .slice(0, array.width() + cursor_width, background)
+            .map_indexed(|x, _, color| match &foreground {
+                Some(fore) if cursor_on && hidden <= x && x < hidden + cursor_width => fore.clone(),
+                _ if x >= hidden => background.clone(),
+                _ => color.clone(),
+            });
+
+        if canvas.width() <= width {
+            place(&canvas, width, scene.align(), background)
+        } else {
+            let end = (hidden + cursor_width) as isize;
+            canvas.slice((end - width as isize).max(0), width, background)
+        }
+    };
+
+    let mut frames: Vec<Frame> = Vec::new();
+    let mut last = None;
+
+    for i in 0..total {
+        let shown = ((i as f32 * scene.cps() / fps).floor() as usize).min(count);
+        let cursor_on = cursor.is_some() && (i < typing || ((i - typing) / half) & 1 == 0);
+
+        let state = (shown, cursor_on);
+        if layout.effects.is_empty() && last == Some(state) {
+            if let Some(frame) = frames.last_mut() {
+                frame.hold += 1;
+            }
+            continue;
+        }
+        last = Some(state);
+
+        frames.push(Frame {
+            array: render(i, shown, cursor_on),
+            hold: 1,
+        });
+    }
+
+    Ok(frames)
+}
@@ -0,0 +1,57 @@
+use super::*;
+use crate::source::Metadata;
+
+/// Holds and lit dots of each frame typing `scene` on a panel of `width` at 10 fps.
+fn typed(scene: &str, width: usize) -> Vec<(usize, usize)> {
+    let meta: Metadata = serde_yaml::from_str(
+        "{colors: {_background: '#000000', _foreground: '#ffffff'}, font: test}",
+    )
+    .unwrap();
+    let renderer = Renderer::with_test_font(&meta);
+    let scene: Scene = serde_yaml::from_str(scene).unwrap();
+    let (array, layout) = renderer.build(scene.body()).unwrap();
+
+    typewriter_frames(&array, &layout, &scene, &renderer, width, 10.0)
+        .unwrap()
+        .iter()
+        .map(|f| {
+            let lit = f.array.iter().filter(|(_, _, c)| c.r::<u8>() > 0).count();
+            (f.hold, lit)
+        })
+        .collect()
+}
+
+#[test]
+fn test_reveal() {
+    let frames = typed("{mode: typewriter, cps: 5, body: [text: {c: abc}]}", 4);
+
+    assert_eq!(frames, [(2, 0), (2, 1), (2, 2), (1, 3)]);
+}
+
+#[test]
+fn test_reveal_until_duration() {
+    let frames = typed(
+        "{mode: typewriter, cps: 10, duration: 1, body: [text: {c: ab}]}",
+        4,
+    );
+
+    assert_eq!(frames, [(1, 0), (1, 1), (8, 2)]);
+}
+
+#[test]
+fn test_cursor() {
+    let frames = typed(
+        "{mode: typewriter, cps: 5, duration: 2, cursor: true, body: [text: {c: abc}]}",
+        4,
+    );
+
+    // The cursor stays on while typing, then blinks every half second.
+    assert_eq!(frames, [(2, 1), (2, 2), (2, 3), (5, 4), (5, 3), (4, 4)]);
+}
+
+#[test]
+fn test_follow_cursor() {
+    let frames = typed("{mode: typewriter, cps: 10, body: [text: {c: abcd}]}", 2);
+
+    assert_eq!(frames, [(1, 0), (1, 1), (1, 2), (1, 2), (1, 2)]);
+}
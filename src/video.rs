@@ -0,0 +1,145 @@
+#[cfg(all(test, unix))]
+mod test;
+
+use std::io::{ErrorKind, Write};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+use failure::format_err;
+
+use crate::frame::Frame;
+use crate::model::dot_array::DotArray;
+use crate::model::Imageable;
+use crate::result::Result;
+use crate::save;
+
+/// How ffmpeg encodes the video.
+#[derive(Debug, Clone)]
+pub struct VideoOptions {
+    /// The ffmpeg executable.
+    pub program: String,
+    pub codec: String,
+    pub pix_fmt: String,
+    pub crf: Option<String>,
+    pub bitrate: Option<String>,
+    /// Container given as a file extension, e.g. `mkv`, passed to `-f` as the muxer name.
+    /// ffmpeg guesses it from the output name if not set.
+    pub container: Option<String>,
+    pub output: PathBuf,
+}
+
+/// Pipes frames as raw RGBA video into ffmpeg. A held frame is written repeatedly.
+pub fn encode_video(
+    frames: &[Frame],
+    dot_arr: &DotArray,
+    fps: f32,
+    options: &VideoOptions,
+) -> Result<()> {
+    if options.crf.is_some() && options.bitrate.is_some() {
+        return Err(format_err!("crf and bitrate cannot be used together"));
+    }
+
+    let first = frames
+        .first()
+        .ok_or_else(|| format_err!("no frames to encode"))?;
+    let (width, height) = dot_arr.with_array(first.array.clone()).dim();
+
+    let mut args = vec![
+        "-y".to_string(),
+        "-f".to_string(),
+        "rawvideo".to_string(),
+        "-pix_fmt".to_string(),
+        "rgba".to_string(),
+        "-s".to_string(),
+        format!("{}x{}", width, height),
+        "-r".to_string(),
+        fps.to_string(),
+        "-i".to_string(),
+        "-".to_string(),
+        "-vcodec".to_string(),
+        options.codec.clone(),
+        "-pix_fmt".to_string(),
+        options.pix_fmt.clone(),
+    ];
+    if let Some(crf) = &options.crf {
+        args.extend(vec!["-crf".to_string(), crf.clone()]);
+    }
+    if let Some(bitrate) = &options.bitrate {
+        args.extend(vec!["-b:v".to_string(), bitrate.clone()]);
+    }
+    if let Some(container) = &options.container {
+        args.extend(vec!["-f".to_string(), muxer(container).to_string()]);
+    }
+    args.push(options.output.to_string_lossy().to_string());
+
+    eprintln!("====vv ffmpeg encoding vv====");
+
+    let mut child = Command::new(&options.program)
+        .args(&args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::inherit())
+        .stderr(Stdio::inherit())
+        .spawn()
+        .map_err(|e| format_err!("cannot run '{}': {}", options.program, e))?;
+
+    let written = write_frames(
+        &mut child.stdin.take().unwrap(),
+        frames,
+        dot_arr,
+        (width, height),
+    );
+    let status = child.wait()?;
+
+    eprintln!("====^^ ffmpeg encoding ^^====");
+
+    if !status.success() {
+        return Err(format_err!(
+            "ffmpeg encoding failed({})",
+            status
+                .code()
+                .map(|c| c.to_string())
+                .unwrap_or_else(|| "?".to_string())
+        ));
+    }
+
+    written
+}
+
+/// ffmpeg muxer of the container with the file extension `container`.
+/// Other names are passed as they are, so a muxer name can also be given.
+fn muxer(container: &str) -> &str {
+    match container {
+        "mkv" | "mka" => "matroska",
+        "ts" | "m2ts" => "mpegts",
+        "m4v" => "mp4",
+        "ogv" => "ogg",
+        "wmv" => "asf",
+        "qt" => "mov",
+        other => other,
+    }
+}
+
+fn write_frames(
+    w: &mut impl Write,
+    frames: &[Frame],
+    dot_arr: &DotArray,
+    dim: (usize, usize),
+) -> Result<()> {
+    for frame in frames {
+        let image = dot_arr.with_array(frame.array.clone());
+        if image.dim() != dim {
+            return Err(format_err!("frame size differs from the first frame"));
+        }
+
+        let pixels = save::rgba(&image);
+        for _ in 0..frame.hold {
+            match w.write_all(&pixels) {
+                // ffmpeg exited early; its status tells why.
+                Err(ref e) if e.kind() == ErrorKind::BrokenPipe => return Ok(()),
+                result => result?,
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,67 @@
+use std::env;
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+
+use super::*;
+use crate::model::array::Array;
+use crate::model::color::Color;
+
+#[test]
+fn test_pipe_to_fake_ffmpeg() {
+    let dir = env::temp_dir().join(format!("led-image-test-video-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+
+    // Records the arguments and stdin into the output file.
+    let program = dir.join("ffmpeg");
+    fs::write(
+        &program,
+        "#!/bin/sh\nfor a; do out=$a; done\necho \"$@\" > \"$out.args\"\ncat > \"$out\"\n",
+    )
+    .unwrap();
+    fs::set_permissions(&program, fs::Permissions::from_mode(0o755)).unwrap();
+
+    let white = Color::new(255u8, 255u8, 255u8, 255u8);
+    let black = Color::new(0u8, 0u8, 0u8, 255u8);
+    let frames = vec![
+        Frame {
+            array: Array::filled(3, 2, &white),
+            hold: 2,
+        },
+        Frame {
+            array: Array::filled(3, 2, &black),
+            hold: 1,
+        },
+    ];
+    let dot_arr = DotArray::new(Array::filled(3, 2, &white), Array::filled(1, 1, &white));
+
+    let output = dir.join("out.mp4");
+    let options = VideoOptions {
+        program: program.to_string_lossy().to_string(),
+        codec: "libx264".to_string(),
+        pix_fmt: "yuv420p".to_string(),
+        crf: Some("20".to_string()),
+        bitrate: None,
+        container: None,
+        output: output.clone(),
+    };
+    encode_video(&frames, &dot_arr, 30.0, &options).unwrap();
+
+    let data = fs::read(&output).unwrap();
+    let args = fs::read_to_string(dir.join("out.mp4.args")).unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(data.len(), 3 * 2 * 4 * 3);
+    assert_eq!(&data[..4], &[255, 255, 255, 255]);
+    assert_eq!(&data[data.len() - 4..], &[0, 0, 0, 255]);
+    assert!(args.contains("-f rawvideo -pix_fmt rgba -s 3x2 -r 30 -i -"));
+    assert!(args.contains("-crf 20"));
+}
+
+#[test]
+fn test_muxer() {
+    assert_eq!(muxer("mkv"), "matroska");
+    assert_eq!(muxer("ts"), "mpegts");
+    assert_eq!(muxer("mp4"), "mp4");
+    assert_eq!(muxer("webm"), "webm");
+    assert_eq!(muxer("matroska"), "matroska");
+}
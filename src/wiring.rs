@@ -0,0 +1,55 @@
+#[cfg(test)]
+mod test;
+
+use failure::format_err;
+
+use crate::export::scan;
+use crate::result::Result;
+use crate::source::Wiring;
+
+/// Logical `(x, y)` of each physical LED, in the order of the data.
+///
+/// Fails if an LED falls outside of a `width` x `height` frame.
+pub fn led_order(wiring: &Wiring, width: usize, height: usize) -> Result<Vec<(usize, usize)>> {
+    let (pw, ph) = wiring.panel_size();
+    let leds = scan(pw, ph, wiring.leds());
+    let mut order = Vec::with_capacity(leds.len() * wiring.chain().len());
+
+    for (i, place) in wiring.chain().iter().enumerate() {
+        let (px, py) = place.position();
+
+        for &(u, v) in &leds {
+            let u = if place.flip() { pw - 1 - u } else { u };
+
+            let (x, y) = match place.rotate() {
+                0 => (u, v),
+                90 => (ph - 1 - v, u),
+                180 => (pw - 1 - u, ph - 1 - v),
+                270 => (v, pw - 1 - u),
+                r => {
+                    return Err(format_err!(
+                        "meta.wiring.chain[{}]: rotate must be 0, 90, 180 or 270, but {}",
+                        i,
+                        r
+                    ))
+                }
+            };
+            let (x, y) = (px + x, py + y);
+
+            if x >= width || y >= height {
+                return Err(format_err!(
+                    "meta.wiring.chain[{}]: LED at ({}, {}) is outside of the {}x{} frame",
+                    i,
+                    x,
+                    y,
+                    width,
+                    height
+                ));
+            }
+
+            order.push((x, y));
+        }
+    }
+
+    Ok(order)
+}
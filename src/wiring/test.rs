@@ -0,0 +1,53 @@
+use super::*;
+
+fn wiring(yaml: &str) -> Wiring {
+    serde_yaml::from_str(yaml).unwrap()
+}
+
+#[test]
+fn test_chain() {
+    // Two 2x2 zigzag panels, the second one upside down to the right.
+    let wiring = wiring(
+        "
+panel-width: 2
+panel-height: 2
+leds: serpentine-rows
+chain:
+  - {x: 0, y: 0}
+  - {x: 2, y: 0, rotate: 180}
+",
+    );
+
+    assert_eq!(
+        led_order(&wiring, 4, 2).unwrap(),
+        vec![
+            (0, 0),
+            (1, 0),
+            (1, 1),
+            (0, 1),
+            (3, 1),
+            (2, 1),
+            (2, 0),
+            (3, 0)
+        ]
+    );
+}
+
+#[test]
+fn test_rotate_flip() {
+    // A 3x1 strip standing upright, wired from the bottom.
+    let wiring = wiring(
+        "
+panel-width: 3
+panel-height: 1
+chain:
+  - {x: 1, y: 0, rotate: 90, flip: true}
+",
+    );
+
+    assert_eq!(
+        led_order(&wiring, 2, 3).unwrap(),
+        vec![(1, 2), (1, 1), (1, 0)]
+    );
+    assert!(led_order(&wiring, 1, 3).is_err());
+}
@@ -0,0 +1,139 @@
+#[cfg(test)]
+mod test;
+
+use failure::format_err;
+
+use crate::effect;
+use crate::frame::{frame_count, Frame};
+use crate::model::array::Array;
+use crate::render::Renderer;
+use crate::result::Result;
+use crate::scroll::scroll_frames;
+use crate::source::{Metadata, Mode, Zone};
+use crate::timeline::place;
+
+/// Renders each zone on its own and composites them into frames of the whole panel.
+///
+/// Every zone loops its own frames. The animation lasts `meta.duration`,
+/// or as long as the longest zone if it is not set.
+pub fn zone_frames(zones: &[Zone], meta: &Metadata, renderer: &Renderer) -> Result<Vec<Frame>> {
+    if zones.is_empty() {
+        return Err(format_err!("zones must not be empty"));
+    }
+
+    let mut layers = Vec::new();
+
+    for (i, zone) in zones.iter().enumerate() {
+        let frames =
+            zone_layer(zone, meta, renderer).map_err(|e| format_err!("zones[{}]: {}", i, e))?;
+
+        // Index into `frames` for each output frame of one loop.
+        let indices = frames
+            .iter()
+            .enumerate()
+            .flat_map(|(j, frame)| (0..frame.hold).map(move |_| j))
+            .collect::<Vec<_>>();
+        if indices.is_empty() {
+            return Err(format_err!("zones[{}]: no frames", i));
+        }
+
+        layers.push((zone, frames, indices));
+    }
+
+    let width = meta.panel_width().unwrap_or_else(|| {
+        zones
+            .iter()
+            .map(|zone| zone.position().0 + zone.width())
+            .max()
+            .unwrap_or(0)
+    });
+    let height = layers
+        .iter()
+        .map(|(zone, frames, _)| zone.position().1 + frames[0].array.height())
+        .max()
+        .unwrap_or(0);
+
+    let total = match meta.duration() {
+        Some(duration) => {
+            let fps = meta
+                .fps()
+                .ok_or_else(|| format_err!("duration requires meta.fps"))?;
+            frame_count(duration, fps)?
+        }
+        None => layers
+            .iter()
+            .map(|(_, _, indices)| indices.len())
+            .max()
+            .unwrap_or(0),
+    };
+
+    let blank = Array::new(height).slice(0, width, renderer.background());
+    let mut frames: Vec<Frame> = Vec::new();
+    let mut last = None;
+
+    for i in 0..total {
+        let current = layers
+            .iter()
+            .map(|(_, _, indices)| indices[i % indices.len()])
+            .collect::<Vec<_>>();
+
+        if last.as_ref() == Some(&current) {
+            if let Some(frame) = frames.last_mut() {
+                frame.hold += 1;
+            }
+            continue;
+        }
+
+        let mut array = blank.clone();
+        for ((zone, zone_frames, _), &j) in layers.iter().zip(&current) {
+            let (x, y) = zone.position();
+            array.paste(&zone_frames[j].array, x, y);
+        }
+
+        frames.push(Frame { array, hold: 1 });
+        last = Some(current);
+    }
+
+    Ok(frames)
+}
+
+fn zone_layer(zone: &Zone, meta: &Metadata, renderer: &Renderer) -> Result<Vec<Frame>> {
+    let (array, layout) = renderer.build(zone.body())?;
+    let background = renderer.background();
+
+    match zone.mode() {
+        Mode::Scroll => {
+            let scroll = zone
+                .scroll(meta)
+                .ok_or_else(|| format_err!("scroll zone requires scroll setting"))?;
+            scroll_frames(&array, &layout, &scroll, background, renderer.fps())
+        }
+        Mode::Static => {
+            let height = zone.height().unwrap_or_else(|| array.height());
+            let panel = |array: &Array| {
+                place(array, zone.width(), zone.align(), background)
+                    .slice_vertical(0, height, background)
+            };
+
+            if layout.effects.is_empty() {
+                return Ok(vec![Frame {
+                    array: panel(&array),
+                    hold: 1,
+                }]);
+            }
+
+            let fps = renderer
+                .fps()
+                .ok_or_else(|| format_err!("text effects require meta.fps"))?;
+            let frames = (0..effect::cycle_frames(&layout.effects, fps)?)
+                .map(|i| Frame {
+                    array: panel(&effect::apply(&array, &layout.effects, i as f32 / fps)),
+                    hold: 1,
+                })
+                .collect();
+
+            Ok(frames)
+        }
+        mode => Err(format_err!("{:?} mode is not supported in zones", mode)),
+    }
+}
@@ -0,0 +1,82 @@
+use super::*;
+
+fn frames(meta: &str, zones: &str) -> Vec<Frame> {
+    let meta: Metadata = serde_yaml::from_str(meta).unwrap();
+    let zones: Vec<Zone> = serde_yaml::from_str(zones).unwrap();
+    zone_frames(&zones, &meta, &Renderer::with_test_font(&meta)).unwrap()
+}
+
+/// Rows of a frame, with 1 for a lit dot.
+fn dots(frame: &Frame) -> Vec<Vec<u8>> {
+    (0..frame.array.height())
+        .map(|y| {
+            (0..frame.array.width())
+                .map(|x| (frame.array.get_line(x).unwrap()[y].r::<u8>() > 0) as u8)
+                .collect()
+        })
+        .collect()
+}
+
+const META: &str =
+    "{colors: {_background: '#000000', _foreground: '#ffffff'}, font: test, width: 5}";
+
+#[test]
+fn test_paste_offsets() {
+    let frames = frames(
+        META,
+        "
+- {width: 2, body: [text: {c: a}]}
+- {x: 3, y: 1, width: 2, align: right, body: [text: {c: a}]}
+",
+    );
+
+    assert_eq!(frames.len(), 1);
+    assert_eq!(dots(&frames[0]), [[1, 0, 0, 0, 0], [0, 0, 0, 0, 1]]);
+}
+
+#[test]
+fn test_clip_to_zone() {
+    let frames = frames(META, "- {x: 1, width: 2, body: [text: {c: abc}]}");
+
+    assert_eq!(dots(&frames[0]), [[0, 1, 1, 0, 0]]);
+}
+
+#[test]
+fn test_clip_to_panel() {
+    let frames = frames(META, "- {x: 4, width: 3, body: [text: {c: abc}]}");
+
+    assert_eq!(dots(&frames[0]), [[0, 0, 0, 0, 1]]);
+}
+
+#[test]
+fn test_panel_size_from_zones() {
+    let frames = frames(
+        "{colors: {_background: '#000000', _foreground: '#ffffff'}, font: test}",
+        "
+- {width: 1, body: [text: {c: a}]}
+- {x: 2, y: 2, width: 2, body: [text: {c: ab}]}
+",
+    );
+
+    assert_eq!(dots(&frames[0]), [[1, 0, 0, 0], [0, 0, 0, 0], [0, 0, 1, 1]]);
+}
+
+#[test]
+fn test_empty_zones() {
+    let meta: Metadata = serde_yaml::from_str(META).unwrap();
+    let err = zone_frames(&[], &meta, &Renderer::with_test_font(&meta)).unwrap_err();
+
+    assert_eq!(err.to_string(), "zones must not be empty");
+}
+
+#[test]
+fn test_zero_duration() {
+    let meta: Metadata = serde_yaml::from_str(
+        "{colors: {_background: '#000000', _foreground: '#ffffff'}, font: test, fps: 10, duration: 0}",
+    )
+    .unwrap();
+    let zones: Vec<Zone> = serde_yaml::from_str("- {width: 1, body: [text: {c: a}]}").unwrap();
+    let err = zone_frames(&zones, &meta, &Renderer::with_test_font(&meta)).unwrap_err();
+
+    assert_eq!(err.to_string(), "duration must be positive, got 0");
+}